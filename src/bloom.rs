@@ -0,0 +1,161 @@
+use raylib::math::Vector3;
+
+// Bloom: post-proceso sobre el color HDR lineal para que lo que emite mucha luz
+// (el sol, el neón cyber) derrame un halo sobre lo que tiene alrededor.
+//
+//   1. bright pass: se queda con lo que supera `threshold` (con rodilla suave)
+//      y lo reduce a media resolución
+//   2. cadena de niveles: cada uno es la mitad del anterior y se difumina con un
+//      Gaussiano separable (horizontal y después vertical)
+//   3. se suben los niveles del más chico al más grande sumándolos y el resultado
+//      se agrega al color original, antes de exposición y tone mapping
+
+// Ancho de la rodilla relativo al umbral: evita el corte duro donde empieza el brillo
+const KNEE: f32 = 0.5;
+
+// Gaussiano de 5 muestras (1 4 6 4 1) / 16
+const WEIGHTS: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bloom {
+    pub enabled: bool,
+    pub threshold: f32, // brillo lineal a partir del cual algo "brilla"
+    pub intensity: f32, // cuánto halo se suma a la imagen
+    pub levels: u32,    // niveles de la cadena: más niveles = halo más ancho
+}
+
+impl Bloom {
+    pub const OFF: Bloom = Bloom {
+        enabled: false,
+        threshold: 1.0,
+        intensity: 0.8,
+        levels: 5,
+    };
+
+    /// Imagen con el halo sumado, o `None` si el bloom no aporta nada
+    pub fn composite(&self, color: &[Vector3], width: u32, height: u32) -> Option<Vec<Vector3>> {
+        if !self.enabled || self.intensity <= 0.0 || self.levels == 0 || width < 2 || height < 2 {
+            return None;
+        }
+        let source = Level {
+            width: width as usize,
+            height: height as usize,
+            pixels: color.to_vec(),
+        };
+
+        // Cadena de niveles difuminados, de media resolución hacia abajo
+        let mut chain = vec![source.downsample(|c| self.bright_pass(c))];
+        chain[0].blur();
+        while chain.len() < self.levels as usize {
+            let last = chain.last().unwrap();
+            if last.width < 4 || last.height < 4 {
+                break;
+            }
+            let mut next = last.downsample(|c| c);
+            next.blur();
+            chain.push(next);
+        }
+
+        // Del nivel más chico al más grande: cada uno suma el de abajo ampliado.
+        // Se vuelve a difuminar la suma para que no se noten los bloques del
+        // muestreo bilineal de los niveles chicos
+        let count = chain.len();
+        let mut glow = chain.pop().unwrap();
+        while let Some(mut level) = chain.pop() {
+            level.add_upsampled(&glow, 1.0);
+            level.blur();
+            glow = level;
+        }
+
+        let mut out = source;
+        out.add_upsampled(&glow, self.intensity / count as f32);
+        Some(out.pixels)
+    }
+
+    // Parte de la luz que supera el umbral, con una transición cuadrática en la rodilla
+    fn bright_pass(&self, color: Vector3) -> Vector3 {
+        let brightness = color.x.max(color.y).max(color.z);
+        if brightness <= 0.0 {
+            return Vector3::zero();
+        }
+        let knee = self.threshold * KNEE;
+        let soft = (brightness - self.threshold + knee).clamp(0.0, 2.0 * knee);
+        let soft = soft * soft / (4.0 * knee + 1e-5);
+        let contribution = soft.max(brightness - self.threshold) / brightness;
+        color * contribution
+    }
+}
+
+// Una imagen de la cadena
+struct Level {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector3>,
+}
+
+impl Level {
+    fn get(&self, x: isize, y: isize) -> Vector3 {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        self.pixels[y * self.width + x]
+    }
+
+    // Mitad de resolución promediando bloques de 2x2 (después de aplicar `filter`)
+    fn downsample(&self, filter: impl Fn(Vector3) -> Vector3) -> Level {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = (2 * x as isize, 2 * y as isize);
+                let sum = filter(self.get(sx, sy))
+                    + filter(self.get(sx + 1, sy))
+                    + filter(self.get(sx, sy + 1))
+                    + filter(self.get(sx + 1, sy + 1));
+                pixels.push(sum * 0.25);
+            }
+        }
+        Level { width, height, pixels }
+    }
+
+    // Gaussiano separable: una pasada horizontal y otra vertical
+    fn blur(&mut self) {
+        for (dx, dy) in [(1, 0), (0, 1)] {
+            let mut pixels = Vec::with_capacity(self.pixels.len());
+            for y in 0..self.height as isize {
+                for x in 0..self.width as isize {
+                    let mut sum = Vector3::zero();
+                    for (k, w) in WEIGHTS.iter().enumerate() {
+                        let o = k as isize - 2;
+                        sum += self.get(x + o * dx, y + o * dy) * *w;
+                    }
+                    pixels.push(sum);
+                }
+            }
+            self.pixels = pixels;
+        }
+    }
+
+    // Muestreo bilineal con los centros de píxel alineados entre resoluciones
+    fn sample(&self, u: f32, v: f32) -> Vector3 {
+        let fx = u * self.width as f32 - 0.5;
+        let fy = v * self.height as f32 - 0.5;
+        let (x0, y0) = (fx.floor(), fy.floor());
+        let (tx, ty) = (fx - x0, fy - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        let top = self.get(x0, y0) * (1.0 - tx) + self.get(x0 + 1, y0) * tx;
+        let bottom = self.get(x0, y0 + 1) * (1.0 - tx) + self.get(x0 + 1, y0 + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    // Suma `other` (de menor resolución) ampliado a este tamaño
+    fn add_upsampled(&mut self, other: &Level, weight: f32) {
+        for y in 0..self.height {
+            let v = (y as f32 + 0.5) / self.height as f32;
+            for x in 0..self.width {
+                let u = (x as f32 + 0.5) / self.width as f32;
+                self.pixels[y * self.width + x] += other.sample(u, v) * weight;
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::matrix::clip_to_screen;
+use crate::vertex::Vertex;
+use raylib::prelude::*;
+
+// Planos del frustum en espacio de clip: un punto está dentro si dist >= 0
+//   -w <= x <= w,  -w <= y <= w,  -w <= z <= w
+const PLANES: [fn(&Vector4) -> f32; 6] = [
+    |p| p.w + p.x, // izquierda
+    |p| p.w - p.x, // derecha
+    |p| p.w + p.y, // abajo
+    |p| p.w - p.y, // arriba
+    |p| p.w + p.z, // cercano
+    |p| p.w - p.z, // lejano
+];
+
+// Interpola todos los atributos del vértice en espacio de clip
+fn lerp_vertex(a: &Vertex, b: &Vertex, t: f32) -> Vertex {
+    Vertex {
+        position: a.position.lerp(b.position, t),
+        normal: a.normal.lerp(b.normal, t),
+        tex_coords: a.tex_coords.lerp(b.tex_coords, t),
+        color: a.color.lerp(b.color, t),
+        world_position: a.world_position.lerp(b.world_position, t),
+        clip_position: a.clip_position.lerp(b.clip_position, t),
+        transformed_position: a.transformed_position.lerp(b.transformed_position, t),
+        transformed_normal: a.transformed_normal.lerp(b.transformed_normal, t),
+    }
+}
+
+// Sutherland–Hodgman contra un plano
+fn clip_polygon(polygon: &[Vertex], plane: fn(&Vector4) -> f32) -> Vec<Vertex> {
+    let mut out = Vec::with_capacity(polygon.len() + 2);
+
+    for i in 0..polygon.len() {
+        let current = &polygon[i];
+        let next = &polygon[(i + 1) % polygon.len()];
+        let d_current = plane(&current.clip_position);
+        let d_next = plane(&next.clip_position);
+
+        if d_current >= 0.0 {
+            out.push(current.clone());
+        }
+        // La arista cruza el plano → agregamos la intersección
+        if (d_current >= 0.0) != (d_next >= 0.0) {
+            let t = d_current / (d_current - d_next);
+            out.push(lerp_vertex(current, next, t));
+        }
+    }
+
+    out
+}
+
+/// Recorta un triángulo contra el frustum y lo re-triangula en abanico.
+/// Los vértices nuevos reciben su posición de pantalla a partir del clip.
+pub fn clip_triangle(v0: &Vertex, v1: &Vertex, v2: &Vertex, viewport: &Matrix) -> Vec<[Vertex; 3]> {
+    let tri = [v0, v1, v2];
+
+    // Caso rápido: completamente dentro
+    if tri.iter().all(|v| PLANES.iter().all(|plane| plane(&v.clip_position) >= 0.0)) {
+        return vec![[v0.clone(), v1.clone(), v2.clone()]];
+    }
+
+    // Caso rápido: los tres vértices fuera del mismo plano
+    if PLANES.iter().any(|plane| tri.iter().all(|v| plane(&v.clip_position) < 0.0)) {
+        return Vec::new();
+    }
+
+    let mut polygon = vec![v0.clone(), v1.clone(), v2.clone()];
+    for plane in PLANES {
+        polygon = clip_polygon(&polygon, plane);
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+    }
+
+    for v in &mut polygon {
+        v.transformed_position = clip_to_screen(&v.clip_position, viewport);
+    }
+
+    (1..polygon.len() - 1)
+        .map(|i| [polygon[0].clone(), polygon[i].clone(), polygon[i + 1].clone()])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::create_viewport_matrix;
+
+    // Vértice con la posición de clip dada y atributos que dependen de ella,
+    // para poder seguir la interpolación
+    fn vertex(x: f32, y: f32, z: f32, w: f32) -> Vertex {
+        let mut v = Vertex::new(Vector3::new(x, y, z), Vector3::new(0.0, 0.0, 1.0), Vector2::new(x, y));
+        v.clip_position = Vector4::new(x, y, z, w);
+        v.color = Vector3::new(z, x, y);
+        v.world_position = Vector3::new(x * 10.0, y * 10.0, z * 10.0);
+        v
+    }
+
+    fn viewport() -> Matrix {
+        create_viewport_matrix(0.0, 0.0, 100.0, 100.0)
+    }
+
+    fn near_distance(v: &Vertex) -> f32 {
+        v.clip_position.w + v.clip_position.z
+    }
+
+    #[test]
+    fn inside_is_unchanged() {
+        let (a, b, c) = (vertex(-0.5, -0.5, 0.1, 1.0), vertex(0.5, -0.5, 0.2, 1.0), vertex(0.0, 0.5, 0.3, 1.0));
+        let out = clip_triangle(&a, &b, &c, &viewport());
+        assert_eq!(out.len(), 1);
+        for (got, original) in out[0].iter().zip([&a, &b, &c]) {
+            assert_eq!(got.clip_position, original.clip_position);
+            assert_eq!(got.transformed_position, original.transformed_position);
+            assert_eq!(got.tex_coords, original.tex_coords);
+        }
+    }
+
+    #[test]
+    fn outside_one_plane_is_dropped() {
+        // Los tres a la derecha de x = w
+        let (a, b, c) = (vertex(2.0, 0.0, 0.0, 1.0), vertex(3.0, 0.5, 0.0, 1.0), vertex(2.5, -0.5, 0.5, 1.0));
+        assert!(clip_triangle(&a, &b, &c, &viewport()).is_empty());
+        // Los tres detrás del plano cercano
+        let (a, b, c) = (vertex(0.0, 0.0, -2.0, 1.0), vertex(0.5, 0.0, -3.0, 1.0), vertex(0.0, 0.5, -1.5, 1.0));
+        assert!(clip_triangle(&a, &b, &c, &viewport()).is_empty());
+    }
+
+    #[test]
+    fn crossing_near_plane_stays_in_frustum() {
+        // Uno detrás del plano cercano y dos delante
+        let (a, b, c) = (vertex(0.0, 0.0, -2.0, 1.0), vertex(0.5, 0.0, 0.0, 1.0), vertex(0.0, 0.5, 0.5, 1.0));
+        let out = clip_triangle(&a, &b, &c, &viewport());
+        assert!((1..=2).contains(&out.len()), "{} triángulos", out.len());
+        for v in out.iter().flatten() {
+            let p = v.clip_position;
+            assert!(p.w > 0.0);
+            assert!(p.z >= -p.w - 1e-5 && p.z <= p.w + 1e-5, "z = {}, w = {}", p.z, p.w);
+        }
+
+        // Dos delante y uno detrás: sale un cuadrilátero (dos triángulos)
+        let (a, b, c) = (vertex(0.0, 0.0, 0.5, 1.0), vertex(0.5, 0.0, -2.0, 1.0), vertex(0.0, 0.5, 0.5, 1.0));
+        assert_eq!(clip_triangle(&a, &b, &c, &viewport()).len(), 2);
+    }
+
+    #[test]
+    fn intersections_interpolate_attributes() {
+        let (a, b, c) = (vertex(0.0, 0.0, -2.0, 1.0), vertex(0.5, 0.0, 0.0, 1.0), vertex(0.0, 0.5, 0.5, 1.0));
+        // Las aristas que cruzan el plano cercano: a→b y c→a
+        let t_ab = near_distance(&a) / (near_distance(&a) - near_distance(&b));
+        let t_ca = near_distance(&c) / (near_distance(&c) - near_distance(&a));
+        let expected = [lerp_vertex(&a, &b, t_ab), lerp_vertex(&c, &a, t_ca)];
+
+        let out = clip_triangle(&a, &b, &c, &viewport());
+        let new_vertices: Vec<&Vertex> = out.iter().flatten().filter(|v| near_distance(v).abs() < 1e-5).collect();
+        assert!(!new_vertices.is_empty());
+        for v in new_vertices {
+            let matches = expected.iter().any(|e| {
+                (v.tex_coords - e.tex_coords).length() < 1e-5
+                    && (v.color - e.color).length() < 1e-5
+                    && (v.world_position - e.world_position).length() < 1e-5
+            });
+            assert!(matches, "vértice de corte sin interpolar bien: {:?}", v);
+            // Y su posición de pantalla sale del clip recortado
+            assert_eq!(v.transformed_position, clip_to_screen(&v.clip_position, &viewport()));
+        }
+    }
+}
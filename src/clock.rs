@@ -0,0 +1,158 @@
+// Reloj de simulación: separa el tiempo del sistema del frame rate
+
+// Unidades de simulación por segundo real a velocidad x1
+// (equivale al antiguo `t += 0.01` por frame a 60 FPS)
+const BASE_RATE: f32 = 0.6;
+
+// Duración de un paso manual, en segundos reales a la escala actual
+const STEP_SECONDS: f32 = 1.0 / 60.0;
+
+// Un frame muy largo (ventana arrastrada, pausa del depurador...) no debe dar un salto enorme
+const MAX_FRAME_SECONDS: f32 = 0.1;
+
+const MIN_SCALE: f32 = 0.1;
+const MAX_SCALE: f32 = 1000.0;
+
+pub struct SimClock {
+    pub time: f64,    // tiempo de simulación actual (f64: a x1000 llega a 1e6 en minutos)
+    pub scale: f32,   // multiplicador de velocidad; negativo = reproducción en reversa
+    pub paused: bool,
+    pending_steps: i32, // pasos manuales pedidos durante la pausa (con signo)
+}
+
+impl SimClock {
+    pub fn new() -> Self {
+        SimClock {
+            time: 0.0,
+            scale: 1.0,
+            paused: false,
+            pending_steps: 0,
+        }
+    }
+
+    /// Avanza el reloj con el delta real del frame y devuelve el delta de simulación
+    pub fn tick(&mut self, real_dt: f32) -> f32 {
+        let dt = if self.paused {
+            let steps = std::mem::take(&mut self.pending_steps) as f32;
+            steps * STEP_SECONDS * BASE_RATE * self.scale.abs()
+        } else {
+            real_dt.clamp(0.0, MAX_FRAME_SECONDS) * BASE_RATE * self.scale
+        };
+
+        self.time += dt as f64;
+        dt
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.pending_steps = 0;
+    }
+
+    // Un paso hacia adelante (+1) o hacia atrás (-1); solo tiene efecto en pausa
+    pub fn step(&mut self, direction: i32) {
+        if self.paused {
+            self.pending_steps += direction.signum();
+        }
+    }
+
+    pub fn speed_up(&mut self) {
+        self.set_speed(self.scale.abs() * 2.0);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.set_speed(self.scale.abs() * 0.5);
+    }
+
+    pub fn reverse(&mut self) {
+        self.scale = -self.scale;
+    }
+
+    // Cambia la magnitud de la escala conservando el sentido
+    fn set_speed(&mut self, speed: f32) {
+        self.scale = speed.clamp(MIN_SCALE, MAX_SCALE).copysign(self.scale);
+    }
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_STEP: f32 = STEP_SECONDS * BASE_RATE;
+
+    #[test]
+    fn paused_clock_only_moves_on_steps() {
+        let mut clock = SimClock::new();
+        clock.toggle_pause();
+        assert_eq!(clock.tick(0.05), 0.0);
+        assert_eq!(clock.time, 0.0);
+
+        clock.step(1);
+        assert_eq!(clock.tick(0.05), ONE_STEP);
+        // El paso se consume: el frame siguiente vuelve a estar quieto
+        assert_eq!(clock.tick(0.05), 0.0);
+        assert_eq!(clock.time, ONE_STEP as f64);
+    }
+
+    #[test]
+    fn step_back_moves_time_backwards() {
+        let mut clock = SimClock::new();
+        clock.time = 10.0;
+        clock.toggle_pause();
+        clock.step(-1);
+        assert_eq!(clock.tick(0.016), -ONE_STEP);
+        assert_eq!(clock.time, 10.0 - ONE_STEP as f64);
+    }
+
+    #[test]
+    fn steps_are_ignored_while_running() {
+        let mut clock = SimClock::new();
+        clock.step(1);
+        clock.step(1);
+        assert_eq!(clock.tick(0.0), 0.0);
+    }
+
+    #[test]
+    fn reverse_runs_time_backwards() {
+        let mut clock = SimClock::new();
+        clock.reverse();
+        assert!(clock.tick(0.05) < 0.0);
+        assert!(clock.time < 0.0);
+        // En pausa el paso sigue el signo pedido, no el de la escala
+        clock.toggle_pause();
+        clock.step(1);
+        assert_eq!(clock.tick(0.0), ONE_STEP);
+    }
+
+    #[test]
+    fn scale_is_clamped() {
+        let mut clock = SimClock::new();
+        for _ in 0..30 {
+            clock.speed_up();
+        }
+        assert_eq!(clock.scale, MAX_SCALE);
+        for _ in 0..30 {
+            clock.slow_down();
+        }
+        assert_eq!(clock.scale, MIN_SCALE);
+
+        // El límite conserva el sentido
+        clock.reverse();
+        for _ in 0..30 {
+            clock.speed_up();
+        }
+        assert_eq!(clock.scale, -MAX_SCALE);
+    }
+
+    #[test]
+    fn long_frames_are_capped() {
+        let mut clock = SimClock::new();
+        assert_eq!(clock.tick(5.0), MAX_FRAME_SECONDS * BASE_RATE);
+        assert_eq!(clock.tick(-1.0), 0.0);
+    }
+}
@@ -2,16 +2,30 @@ use raylib::math::{Vector2, Vector3};
 
 pub struct Fragment {
     pub position: Vector2,
-    pub color: Vector3,
     pub depth: f32,
+    pub vertex_position: Vector3, // posición en espacio de objeto (interpolada)
+    pub world_position: Vector3,  // para iluminación
+    pub normal: Vector3,          // normal en mundo (interpolada)
+    pub tex_coords: Vector2,
 }
 
 impl Fragment {
-    pub fn new(x: f32, y: f32, color: Vector3, depth: f32) -> Self {
+    pub fn new(
+        x: f32,
+        y: f32,
+        depth: f32,
+        vertex_position: Vector3,
+        world_position: Vector3,
+        normal: Vector3,
+        tex_coords: Vector2,
+    ) -> Self {
         Fragment {
             position: Vector2::new(x, y),
-            color,
             depth,
+            vertex_position,
+            world_position,
+            normal,
+            tex_coords,
         }
     }
 }
@@ -1,25 +1,101 @@
-use raylib::prelude::*;
+use crate::bloom::Bloom;
+use crate::image_io;
+use crate::tonemap::{self, ToneMapping};
+use raylib::math::Vector3;
 
+// Color de pantalla empaquetado como RGBA8 (byte 0 = R ... byte 3 = A)
+pub fn pack_color(color: Vector3) -> u32 {
+    let r = (color.x.clamp(0.0, 1.0) * 255.0) as u32;
+    let g = (color.y.clamp(0.0, 1.0) * 255.0) as u32;
+    let b = (color.z.clamp(0.0, 1.0) * 255.0) as u32;
+    r | (g << 8) | (b << 16) | (255 << 24)
+}
+
+// Cómo se combina un fragmento con el color que ya hay en el píxel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    Opaque,   // reemplaza (ignora alpha)
+    Alpha,    // mezcla clásica: src * a + dst * (1 - a)
+    Additive, // suma luz: brillos, coronas, colas de cometa
+    Multiply, // oscurece/tiñe lo de atrás: humo, sombras coloreadas
+}
+
+impl BlendMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "opaque" => Some(BlendMode::Opaque),
+            "alpha" => Some(BlendMode::Alpha),
+            "additive" => Some(BlendMode::Additive),
+            "multiply" => Some(BlendMode::Multiply),
+            _ => None,
+        }
+    }
+
+    pub fn is_opaque(self) -> bool {
+        self == BlendMode::Opaque
+    }
+
+    pub fn blend(self, dst: Vector3, src: Vector3, alpha: f32) -> Vector3 {
+        let a = alpha.clamp(0.0, 1.0);
+        match self {
+            BlendMode::Opaque => src,
+            BlendMode::Alpha => src * a + dst * (1.0 - a),
+            BlendMode::Additive => dst + src * a,
+            BlendMode::Multiply => dst * (src * a + Vector3::new(1.0, 1.0, 1.0) * (1.0 - a)),
+        }
+    }
+}
+
+// Estado de mezcla de un objeto: modo + si escribe en el z-buffer.
+// Los transparentes hacen test de profundidad pero no escriben, así no tapan
+// lo que se dibuje detrás de ellos después.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlendState {
+    pub mode: BlendMode,
+    pub depth_write: bool,
+}
 
+impl BlendState {
+    pub const OPAQUE: BlendState = BlendState {
+        mode: BlendMode::Opaque,
+        depth_write: true,
+    };
+
+    // Valores por defecto para un modo: solo los opacos escriben profundidad
+    pub fn new(mode: BlendMode) -> Self {
+        BlendState {
+            mode,
+            depth_write: mode.is_opaque(),
+        }
+    }
+}
+
+// Framebuffer en memoria: no depende de raylib, así que sirve para tests y render offline.
+// Guarda luz lineal sin límite (HDR); se pasa a 8 bits solo al presentar o exportar.
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
-    pub image: Image,
     pub background_color: Vector3,
-    pub depth_buffer: Vec<f32>, // ⭐ Z-buffer
+    pub color_buffer: Vec<Vector3>, // color lineal HDR por píxel
+    pub depth_buffer: Vec<f32>,     // ⭐ Z-buffer
+    pub exposure: f32,              // multiplica la luz antes del tone mapping
+    pub tone_mapping: ToneMapping,
+    pub bloom: Bloom, // halo de lo muy brillante, se suma al presentar
 }
 
 impl Framebuffer {
     pub fn new(width: u32, height: u32) -> Self {
-        let image = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
         let size = (width * height) as usize;
 
         Self {
             width,
             height,
-            image,
             background_color: Vector3::new(0.0, 0.0, 0.0),
+            color_buffer: vec![Vector3::zero(); size],
             depth_buffer: vec![f32::INFINITY; size], // Inicializamos todo "infinitamente lejos"
+            exposure: 1.0,
+            tone_mapping: ToneMapping::Aces,
+            bloom: Bloom::OFF,
         }
     }
 
@@ -29,19 +105,17 @@ impl Framebuffer {
 
     /// Limpia el framebuffer y el z-buffer
     pub fn clear(&mut self) {
-        let bg = Color::new(
-            (self.background_color.x.clamp(0.0, 1.0) * 255.0) as u8,
-            (self.background_color.y.clamp(0.0, 1.0) * 255.0) as u8,
-            (self.background_color.z.clamp(0.0, 1.0) * 255.0) as u8,
-            255,
-        );
-
-        self.image.clear_background(bg);
+        self.color_buffer.fill(self.background_color);
         self.depth_buffer.fill(f32::INFINITY);
     }
 
-    /// Dibuja un punto con test de profundidad
+    /// Dibuja un punto opaco con test de profundidad
     pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) {
+        self.blend_point(x, y, color, 1.0, depth, BlendState::OPAQUE);
+    }
+
+    /// Dibuja un punto mezclándolo con lo que ya hay según `blend`
+    pub fn blend_point(&mut self, x: i32, y: i32, color: Vector3, alpha: f32, depth: f32, blend: BlendState) {
         if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
             return;
         }
@@ -53,21 +127,33 @@ impl Framebuffer {
 
         // ⭐ Solo pintamos si el fragmento está más cerca que lo que ya había
         if depth < self.depth_buffer[idx] {
-            self.depth_buffer[idx] = depth;
-
-            let c = Color::new(
-                (color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                (color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                (color.z.clamp(0.0, 1.0) * 255.0) as u8,
-                255,
-            );
-
-            self.image.draw_pixel(x, y, c);
+            if blend.depth_write {
+                self.depth_buffer[idx] = depth;
+            }
+            self.color_buffer[idx] = blend.mode.blend(self.color_buffer[idx], color, alpha);
         }
     }
 
-    /// Guarda el framebuffer en disco
-    pub fn save_image(&mut self, path: &str) {
-        self.image.export_image(path);
+    /// Bytes RGBA8 fila por fila (bloom + exposición + tone mapping + sRGB),
+    /// listos para `update_texture` o para exportar
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        // El bloom trabaja sobre una copia: el buffer queda tal cual se rasterizó
+        let bloomed = self.bloom.composite(&self.color_buffer, self.width, self.height);
+        bloomed
+            .as_deref()
+            .unwrap_or(&self.color_buffer)
+            .iter()
+            .flat_map(|&c| pack_color(tonemap::present(c, self.exposure, self.tone_mapping)).to_le_bytes())
+            .collect()
+    }
+
+    /// Guarda el framebuffer en disco (PPM si la extensión es `.ppm`, si no PNG)
+    pub fn save_image(&self, path: &str) -> std::io::Result<()> {
+        let rgba = self.to_rgba_bytes();
+        if path.ends_with(".ppm") {
+            image_io::write_ppm(path, self.width, self.height, &rgba)
+        } else {
+            image_io::write_png(path, self.width, self.height, &rgba)
+        }
     }
-}
\ No newline at end of file
+}
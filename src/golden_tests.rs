@@ -0,0 +1,531 @@
+// Tests de regresión por imagen: cada escena se renderiza a un tamaño y tiempo
+// fijos y se compara con una referencia en `tests/golden/<nombre>.ppm`.
+//
+//   cargo test                          compara con las referencias
+//   UPDATE_GOLDEN=1 cargo test          regenera las referencias (revisarlas antes de subirlas)
+//
+// Si una escena no coincide, en `target/golden-diff/` quedan la imagen obtenida
+// (`<nombre>.actual.ppm`), la esperada (`<nombre>.expected.ppm`) y un diff
+// (`<nombre>.diff.ppm`) con los píxeles distintos en rojo sobre la obtenida atenuada.
+
+use crate::bloom::Bloom;
+use crate::create_model_matrix;
+use crate::framebuffer::{BlendState, Framebuffer};
+use crate::image_io;
+use crate::matrix::{
+    create_normal_matrix, create_projection_matrix, create_view_matrix, create_viewport_matrix,
+};
+use crate::obj::Obj;
+use crate::raster;
+use crate::shadow::{self, ShadowCaster};
+use crate::shaders::{PlanetShader, ShaderParams, ShaderRegistry, TexturedShader};
+use crate::texture::Texture;
+use crate::tonemap::srgb_color;
+use crate::vertex::Vertex;
+use crate::{Uniforms, BACKGROUND_COLOR};
+use raylib::prelude::*;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const SIZE: u32 = 96;
+
+// Diferencia máxima por canal (0-255) que se considera igual
+const CHANNEL_TOLERANCE: u8 = 3;
+
+// Fracción de píxeles que puede superar la tolerancia (bordes de triángulos
+// con redondeos distintos entre plataformas)
+const MAX_BAD_PIXELS: f32 = 0.002;
+
+// Un objeto de la escena de prueba
+struct Item<'a> {
+    mesh: &'a Obj,
+    shader: Arc<dyn PlanetShader>,
+    translation: Vector3,
+    scale: f32,
+    rotation: Vector3,
+    color1: Vector3,
+    color2: Vector3,
+    time: f32,
+    seed: u32,
+}
+
+fn manifest_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+fn load_mesh(name: &str) -> Obj {
+    let path = manifest_path(&format!("src/{}", name));
+    Obj::load(&path.to_string_lossy()).unwrap_or_else(|e| panic!("no se pudo cargar {}: {:?}", name, e))
+}
+
+// Shader registrado con sus parámetros por defecto
+fn builtin(name: &str) -> Arc<dyn PlanetShader> {
+    seeded(name, 0)
+}
+
+// Igual, con otra semilla para el ruido
+fn seeded(name: &str, seed: u32) -> Arc<dyn PlanetShader> {
+    ShaderRegistry::builtin()
+        .create(name, &ShaderParams::default().with_seed(seed as u64))
+        .unwrap()
+}
+
+fn sphere<'a>(mesh: &'a Obj, shader: &str, color1: Vector3, color2: Vector3) -> Item<'a> {
+    Item {
+        mesh,
+        shader: builtin(shader),
+        translation: Vector3::zero(),
+        scale: 100.0,
+        rotation: Vector3::new(0.3, 0.6, 0.0),
+        color1,
+        color2,
+        time: 0.0,
+        seed: 0,
+    }
+}
+
+// Cámara fija mirando al origen desde -Z, luz arriba a la izquierda
+fn render(items: &[Item]) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    framebuffer.clear();
+
+    let camera_position = Vector3::new(0.0, 0.0, -400.0);
+    let view_matrix = create_view_matrix(
+        camera_position,
+        Vector3::zero(),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let projection_matrix = create_projection_matrix(60.0_f32.to_radians(), 1.0, 10.0, 2000.0);
+    let viewport_matrix = create_viewport_matrix(0.0, 0.0, SIZE as f32, SIZE as f32);
+
+    // Sombras entre los objetos de la escena, como en el sistema
+    let placed: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let model = create_model_matrix(item.translation, item.scale, item.rotation);
+            let shape = item.shader.shadow_shape()?;
+            Some(ShadowCaster::from_mesh(shape, item.mesh).place(&model, &item.shader))
+        })
+        .collect();
+    let occluders = shadow::occluders_for(&placed);
+
+    let mut frame_uniforms = Vec::new();
+    let mut triangles = Vec::new();
+    for (item, occluders) in items.iter().zip(occluders) {
+        let model = create_model_matrix(item.translation, item.scale, item.rotation);
+        let uniforms = Uniforms {
+            model_matrix: model,
+            normal_matrix: create_normal_matrix(&model),
+            view_matrix,
+            projection_matrix,
+            viewport_matrix,
+            light_position: Vector3::new(600.0, 400.0, -500.0),
+            light_radius: 60.0,
+            camera_position,
+            shader: item.shader.clone(),
+            // Colores en sRGB, como en el archivo del sistema
+            base_color1: srgb_color(item.color1),
+            base_color2: srgb_color(item.color2),
+            light_intensity: 1.0,
+            ambient_strength: 0.18,
+            emission_strength: 1.2,
+            time: item.time,
+            seed: item.seed,
+            blend: BlendState::new(item.shader.blend_mode()),
+            material: item.shader.material(),
+            occluders,
+        };
+        raster::assemble(&mut triangles, frame_uniforms.len(), &uniforms, item.mesh);
+        frame_uniforms.push(uniforms);
+    }
+
+    raster::rasterize(&mut framebuffer, &triangles, &frame_uniforms);
+    framebuffer
+}
+
+// Lector mínimo de los PPM que escribe `image_io::write_ppm`
+fn read_ppm(path: &PathBuf) -> Option<(u32, u32, Vec<u8>)> {
+    let data = std::fs::read(path).ok()?;
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let start = pos;
+        while !data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        fields.push(std::str::from_utf8(&data[start..pos]).ok()?.to_string());
+    }
+    pos += 1; // un único espacio separa el encabezado de los píxeles
+
+    if fields[0] != "P6" || fields[3] != "255" {
+        return None;
+    }
+    let width: u32 = fields[1].parse().ok()?;
+    let height: u32 = fields[2].parse().ok()?;
+    let rgb = data.get(pos..pos + (width * height * 3) as usize)?.to_vec();
+    Some((width, height, rgb))
+}
+
+fn to_rgba(rgb: &[u8]) -> Vec<u8> {
+    rgb.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect()
+}
+
+fn assert_golden(name: &str, framebuffer: &Framebuffer) {
+    let reference_path = manifest_path(&format!("tests/golden/{}.ppm", name));
+    let rgba = framebuffer.to_rgba_bytes();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        image_io::write_ppm(&reference_path.to_string_lossy(), SIZE, SIZE, &rgba).unwrap();
+        return;
+    }
+
+    let (width, height, expected) = read_ppm(&reference_path).unwrap_or_else(|| {
+        panic!(
+            "falta la referencia {} (generarla con UPDATE_GOLDEN=1 cargo test)",
+            reference_path.display()
+        )
+    });
+    assert_eq!((width, height), (SIZE, SIZE), "{}: la referencia tiene otro tamaño", name);
+
+    let mut diff = Vec::with_capacity(rgba.len());
+    let mut bad_pixels = 0;
+    for (actual, expected) in rgba.chunks(4).zip(expected.chunks(3)) {
+        let max_delta = (0..3).map(|c| actual[c].abs_diff(expected[c])).max().unwrap();
+        if max_delta > CHANNEL_TOLERANCE {
+            bad_pixels += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            diff.extend(actual[..3].iter().map(|c| c / 3));
+            diff.push(255);
+        }
+    }
+
+    let allowed = (MAX_BAD_PIXELS * (SIZE * SIZE) as f32) as usize;
+    if bad_pixels > allowed {
+        let out_dir = manifest_path("target/golden-diff");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let actual_path = out_dir.join(format!("{}.actual.ppm", name));
+        let diff_path = out_dir.join(format!("{}.diff.ppm", name));
+        image_io::write_ppm(&actual_path.to_string_lossy(), SIZE, SIZE, &rgba).unwrap();
+        image_io::write_ppm(&diff_path.to_string_lossy(), SIZE, SIZE, &diff).unwrap();
+        let expected_path = out_dir.join(format!("{}.expected.ppm", name));
+        image_io::write_ppm(&expected_path.to_string_lossy(), SIZE, SIZE, &to_rgba(&expected))
+            .unwrap();
+
+        panic!(
+            "{}: {} píxeles distintos (máximo {}); ver {}",
+            name,
+            bad_pixels,
+            allowed,
+            diff_path.display()
+        );
+    }
+}
+
+fn white() -> Vector3 {
+    Vector3::new(1.0, 1.0, 1.0)
+}
+
+#[test]
+fn star() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(&mesh, "star", Vector3::new(1.0, 0.9, 0.45), Vector3::new(0.25, 0.18, 0.08));
+    assert_golden("star", &render(&[item]));
+}
+
+#[test]
+fn rocky() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(&mesh, "rocky", Vector3::new(0.22, 0.55, 0.85), Vector3::new(0.05, 0.2, 0.1));
+    assert_golden("rocky", &render(&[item]));
+}
+
+#[test]
+fn gas_giant() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(
+        &mesh,
+        "gas_giant",
+        Vector3::new(0.92, 0.74, 0.46),
+        Vector3::new(0.62, 0.52, 0.34),
+    );
+    assert_golden("gas_giant", &render(&[item]));
+}
+
+// Mismo gigante gaseoso más adelante y con otra semilla: las bandas derivan y
+// la tormenta cambia de sitio, pero el resultado sigue siendo reproducible
+#[test]
+fn gas_giant_animated() {
+    let mesh = load_mesh("planeta.obj");
+    let item = Item {
+        time: 12.0,
+        seed: 3,
+        shader: seeded("gas_giant", 3),
+        ..sphere(
+            &mesh,
+            "gas_giant",
+            Vector3::new(0.92, 0.74, 0.46),
+            Vector3::new(0.62, 0.52, 0.34),
+        )
+    };
+    assert_golden("gas_giant_animated", &render(&[item]));
+}
+
+#[test]
+fn cyber() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(&mesh, "cyber", Vector3::new(0.15, 0.18, 0.22), Vector3::new(0.0, 0.75, 1.0));
+    assert_golden("cyber", &render(&[item]));
+}
+
+#[test]
+fn magma() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(
+        &mesh,
+        "magma",
+        Vector3::new(0.75, 0.15, 0.05),
+        Vector3::new(0.1, 0.02, 0.01),
+    );
+    assert_golden("magma", &render(&[item]));
+}
+
+#[test]
+fn flat() {
+    let mesh = load_mesh("planeta.obj");
+    let item = sphere(&mesh, "flat", Vector3::new(0.8, 0.3, 0.6), white());
+    assert_golden("flat", &render(&[item]));
+}
+
+#[test]
+fn ring() {
+    let mesh = load_mesh("ring.obj");
+    let item = Item {
+        mesh: &mesh,
+        shader: builtin("ring"),
+        translation: Vector3::zero(),
+        scale: 55.0,
+        rotation: Vector3::new(0.5, 0.0, 0.2),
+        color1: Vector3::new(0.65, 0.6, 0.5),
+        color2: Vector3::new(0.85, 0.8, 0.7),
+        time: 0.0,
+        seed: 0,
+    };
+    assert_golden("ring", &render(&[item]));
+}
+
+// Anillo semitransparente alrededor de una esfera, enviado antes que ella: la
+// pasada transparente debe mezclarlo encima de la esfera por delante y dejar
+// que la tape por detrás
+#[test]
+fn transparent_ring() {
+    let sphere_mesh = load_mesh("planeta.obj");
+    let ring_mesh = load_mesh("ring.obj");
+    let ring = Item {
+        mesh: &ring_mesh,
+        shader: builtin("ring"),
+        translation: Vector3::zero(),
+        scale: 55.0,
+        rotation: Vector3::new(0.25, 0.0, 0.2),
+        color1: Vector3::new(0.65, 0.6, 0.5),
+        color2: Vector3::new(0.85, 0.8, 0.7),
+        time: 0.0,
+        seed: 0,
+    };
+    let planet = Item {
+        scale: 45.0,
+        ..sphere(&sphere_mesh, "flat", Vector3::new(0.3, 0.45, 0.9), white())
+    };
+    assert_golden("transparent_ring", &render(&[ring, planet]));
+}
+
+// Gigante gaseoso con anillo: el anillo deja franjas de sombra sobre el
+// planeta y el planeta corta el anillo del lado opuesto al sol
+#[test]
+fn ring_shadow() {
+    let sphere_mesh = load_mesh("planeta.obj");
+    let ring_mesh = load_mesh("ring.obj");
+    let ring = Item {
+        mesh: &ring_mesh,
+        shader: builtin("ring"),
+        translation: Vector3::zero(),
+        scale: 48.0,
+        rotation: Vector3::new(0.55, 0.0, -0.35),
+        color1: Vector3::new(0.75, 0.7, 0.6),
+        color2: Vector3::new(0.9, 0.85, 0.75),
+        time: 0.0,
+        seed: 0,
+    };
+    let planet = Item {
+        scale: 50.0,
+        ..sphere(
+            &sphere_mesh,
+            "gas_giant",
+            Vector3::new(0.92, 0.74, 0.46),
+            Vector3::new(0.62, 0.52, 0.34),
+        )
+    };
+    assert_golden("ring_shadow", &render(&[planet, ring]));
+}
+
+// Una luna entre el sol y el planeta: su sombra cae sobre el planeta con umbra
+// al centro y penumbra alrededor
+#[test]
+fn eclipse() {
+    let mesh = load_mesh("planeta.obj");
+    let to_light = Vector3::new(600.0, 400.0, -500.0).normalized();
+    let planet = Item {
+        scale: 60.0,
+        ..sphere(&mesh, "flat", Vector3::new(0.35, 0.6, 0.4), white())
+    };
+    let moon = Item {
+        translation: to_light * 150.0,
+        scale: 16.0,
+        ..sphere(&mesh, "flat", Vector3::new(0.7, 0.7, 0.7), white())
+    };
+    assert_golden("eclipse", &render(&[planet, moon]));
+}
+
+// Planeta rocoso con su capa de aire: borde azul del lado iluminado, rojizo en
+// el terminador y nada del lado de noche
+#[test]
+fn atmosphere() {
+    let mesh = load_mesh("planeta.obj");
+    let planet = sphere(&mesh, "rocky", Vector3::new(0.22, 0.55, 0.85), Vector3::new(0.05, 0.2, 0.1));
+    let air = Item {
+        rotation: Vector3::zero(),
+        ..sphere(&mesh, "atmosphere", Vector3::new(0.35, 0.6, 1.0), Vector3::new(1.0, 0.45, 0.2))
+    };
+    assert_golden("atmosphere", &render(&[planet, air]));
+}
+
+// Sol y planeta cyber con bloom: el sol y el neón de la rejilla derraman un
+// halo sobre el fondo; el metal oscuro, por debajo del umbral, no
+#[test]
+fn bloom() {
+    let mesh = load_mesh("planeta.obj");
+    let star = Item {
+        translation: Vector3::new(-100.0, 0.0, 0.0),
+        scale: 55.0,
+        ..sphere(&mesh, "star", Vector3::new(1.0, 0.9, 0.45), Vector3::new(0.25, 0.18, 0.08))
+    };
+    let cyber = Item {
+        translation: Vector3::new(100.0, 0.0, 0.0),
+        scale: 55.0,
+        ..sphere(&mesh, "cyber", Vector3::new(0.15, 0.18, 0.22), Vector3::new(0.0, 0.75, 1.0))
+    };
+    let mut framebuffer = render(&[star, cyber]);
+    framebuffer.bloom = Bloom {
+        enabled: true,
+        ..Bloom::OFF
+    };
+    assert_golden("bloom", &framebuffer);
+}
+
+// Planeta cyber en la sombra de un cuerpo grande fuera de cuadro: el metal queda
+// a oscuras, pero el neón emite luz propia y sigue derramando halo
+#[test]
+fn bloom_cyber_eclipse() {
+    let mesh = load_mesh("planeta.obj");
+    let to_light = Vector3::new(600.0, 400.0, -500.0).normalized();
+    let cyber = Item {
+        scale: 40.0,
+        ..sphere(&mesh, "cyber", Vector3::new(0.15, 0.18, 0.22), Vector3::new(0.0, 0.75, 1.0))
+    };
+    let blocker = Item {
+        translation: to_light * 300.0,
+        scale: 60.0,
+        ..sphere(&mesh, "flat", Vector3::new(0.5, 0.5, 0.5), white())
+    };
+    let mut framebuffer = render(&[cyber, blocker]);
+    framebuffer.bloom = Bloom {
+        enabled: true,
+        ..Bloom::OFF
+    };
+    assert_golden("bloom_cyber_eclipse", &framebuffer);
+}
+
+// La esfera cercana se envía primero: el z-buffer debe ocultar la lejana
+// aunque se dibuje después
+#[test]
+fn depth_overlap() {
+    let mesh = load_mesh("planeta.obj");
+    let near = Item {
+        translation: Vector3::new(-40.0, -20.0, -60.0),
+        scale: 70.0,
+        ..sphere(&mesh, "flat", Vector3::new(0.2, 0.8, 0.3), white())
+    };
+    let far = Item {
+        translation: Vector3::new(50.0, 30.0, 120.0),
+        scale: 110.0,
+        ..sphere(&mesh, "flat", Vector3::new(0.9, 0.25, 0.2), white())
+    };
+    assert_golden("depth_overlap", &render(&[near, far]));
+}
+
+fn checkerboard(size: usize) -> Texture {
+    let texels = (0..size * size)
+        .map(|i| {
+            if (i % size + i / size).is_multiple_of(2) {
+                Vector3::new(0.9, 0.85, 0.3)
+            } else {
+                Vector3::new(0.15, 0.3, 0.7)
+            }
+        })
+        .collect();
+    Texture::from_pixels(size, size, texels)
+}
+
+// Tablero de 8x8 por UV: los cuadros deben verse rectos y sin costuras raras
+#[test]
+fn textured() {
+    let mesh = load_mesh("planeta.obj");
+    let shader = TexturedShader {
+        albedo: checkerboard(8),
+        bilinear: true,
+    };
+    let item = Item {
+        shader: Arc::new(shader),
+        ..sphere(&mesh, "flat", white(), white())
+    };
+    assert_golden("textured", &render(&[item]));
+}
+
+// Un piso de dos triángulos enormes que se aleja de la cámara: sin corrección
+// de perspectiva las líneas del tablero salen torcidas en la diagonal
+#[test]
+fn perspective_floor() {
+    let corner = |x: f32, z: f32, u: f32, v: f32| {
+        Vertex::new(Vector3::new(x, 0.0, z), Vector3::new(0.0, 1.0, 0.0), Vector2::new(u, v))
+    };
+    let floor = Obj {
+        vertices: vec![
+            corner(-1.0, -1.0, 0.0, 0.0),
+            corner(1.0, -1.0, 1.0, 0.0),
+            corner(1.0, 1.0, 1.0, 1.0),
+            corner(-1.0, 1.0, 0.0, 1.0),
+        ],
+        indices: vec![0, 2, 1, 0, 3, 2],
+    };
+    let shader = TexturedShader {
+        albedo: checkerboard(8),
+        bilinear: false,
+    };
+    let item = Item {
+        mesh: &floor,
+        shader: Arc::new(shader),
+        translation: Vector3::new(0.0, -80.0, 300.0),
+        scale: 600.0,
+        rotation: Vector3::zero(),
+        color1: white(),
+        color2: white(),
+        time: 0.0,
+        seed: 0,
+    };
+    assert_golden("perspective_floor", &render(&[item]));
+}
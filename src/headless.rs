@@ -0,0 +1,292 @@
+use crate::framebuffer::Framebuffer;
+use crate::recorder::Recorder;
+use crate::tonemap::ToneMapping;
+use crate::{Camera, Space, BACKGROUND_COLOR};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::Path;
+
+// Render sin ventana: mismo pipeline que el modo interactivo, pero los frames
+// van directo a disco. Sirve para stills y renders de regresión en máquinas sin GPU.
+//
+//   cargo run --release -- --headless [sistema.toml] [opciones]
+//
+//   --frames N       cantidad de frames (1)
+//   --time T         tiempo de simulación del primer frame (0)
+//   --step S         tiempo de simulación entre frames, no negativo (0.01, un frame a 60 FPS y x1)
+//   --size WxH       resolución (800x600)
+//   --out DIR        carpeta de salida (render)
+//   --format F       png, ppm o y4m (png); y4m guarda un solo video DIR/render.y4m
+//   --fps N          cuadros por segundo del video (60)
+//   --seed N         semilla del cielo de estrellas (0)
+//   --focus NOMBRE   centra la cámara en un cuerpo, como el warp
+//   --zoom Z         zoom de la cámara (1, o el del warp si hay --focus)
+//   --exposure E     exposición (la del sistema, 1 por defecto)
+//   --tone-mapping T clamp, reinhard o aces (el del sistema, aces por defecto)
+//   --bloom on|off   prende o apaga el bloom (el del sistema, apagado por defecto)
+
+struct Options {
+    system_path: String,
+    frames: u32,
+    time: f64,
+    step: f64,
+    width: u32,
+    height: u32,
+    out_dir: String,
+    format: String,
+    fps: u32,
+    seed: u64,
+    focus: Option<String>,
+    zoom: Option<f32>,
+    exposure: Option<f32>,
+    tone_mapping: Option<ToneMapping>,
+    bloom: Option<bool>,
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("valor inválido para {}: `{}`", flag, value))
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            system_path: "src/sistema.toml".to_string(),
+            frames: 1,
+            time: 0.0,
+            step: 0.01,
+            width: 800,
+            height: 600,
+            out_dir: "render".to_string(),
+            format: "png".to_string(),
+            fps: 60,
+            seed: 0,
+            focus: None,
+            zoom: None,
+            exposure: None,
+            tone_mapping: None,
+            bloom: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--headless" {
+                continue;
+            }
+            if !arg.starts_with("--") {
+                options.system_path = arg.clone();
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("falta el valor de {}", arg))?;
+            match arg.as_str() {
+                "--frames" => options.frames = parse_number(arg, value)?,
+                "--time" => options.time = parse_number(arg, value)?,
+                "--step" => options.step = parse_number(arg, value)?,
+                "--size" => {
+                    let (w, h) = value
+                        .split_once('x')
+                        .ok_or_else(|| format!("--size espera ANCHOxALTO, no `{}`", value))?;
+                    options.width = parse_number(arg, w)?;
+                    options.height = parse_number(arg, h)?;
+                }
+                "--out" => options.out_dir = value.clone(),
+                "--format" => options.format = value.clone(),
+                "--fps" => options.fps = parse_number(arg, value)?,
+                "--seed" => options.seed = parse_number(arg, value)?,
+                "--focus" => options.focus = Some(value.clone()),
+                "--zoom" => options.zoom = Some(parse_number(arg, value)?),
+                "--exposure" => options.exposure = Some(parse_number(arg, value)?),
+                "--tone-mapping" => {
+                    options.tone_mapping = Some(ToneMapping::from_name(value).ok_or_else(|| {
+                        format!("tone mapping desconocido `{}` (clamp, reinhard o aces)", value)
+                    })?)
+                }
+                "--bloom" => {
+                    options.bloom = Some(match value.as_str() {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(format!("--bloom espera on u off, no `{}`", value)),
+                    })
+                }
+                _ => return Err(format!("opción desconocida {}", arg)),
+            }
+        }
+
+        if options.width == 0 || options.height == 0 {
+            return Err("la resolución debe ser mayor que cero".to_string());
+        }
+        if !["png", "ppm", "y4m"].contains(&options.format.as_str()) {
+            return Err(format!("formato desconocido `{}` (png, ppm o y4m)", options.format));
+        }
+        if options.fps == 0 {
+            return Err("--fps debe ser mayor que cero".to_string());
+        }
+        // La cámara divide la distancia por el zoom: 0 o negativo no tienen sentido
+        if options.zoom.is_some_and(|z| !z.is_finite() || z <= 0.0) {
+            return Err("--zoom debe ser un número mayor que cero".to_string());
+        }
+        if options.exposure.is_some_and(|e| !e.is_finite() || e <= 0.0) {
+            return Err("--exposure debe ser un número mayor que cero".to_string());
+        }
+        if !options.time.is_finite() {
+            return Err("--time debe ser un número finito".to_string());
+        }
+        if !options.step.is_finite() || options.step < 0.0 {
+            return Err("--step debe ser un número finito y no negativo".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+/// Renderiza los frames pedidos en `args` y los guarda como imágenes numeradas o video
+pub fn run(args: &[String]) -> Result<(), String> {
+    let options = Options::parse(args)?;
+
+    let mut space = Space::load(&options.system_path, options.width, options.height, options.seed)?;
+    let mut framebuffer = Framebuffer::new(options.width, options.height);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    framebuffer.exposure = options.exposure.unwrap_or(space.system.exposure);
+    framebuffer.tone_mapping = options.tone_mapping.unwrap_or(space.system.tone_mapping);
+    framebuffer.bloom = space.system.bloom;
+    if let Some(enabled) = options.bloom {
+        framebuffer.bloom.enabled = enabled;
+    }
+
+    let focus = match &options.focus {
+        None => None,
+        Some(name) => Some(
+            space
+                .system
+                .bodies
+                .iter()
+                .position(|b| b.name == *name)
+                .ok_or_else(|| format!("no hay ningún cuerpo llamado `{}`", name))?,
+        ),
+    };
+
+    let mut camera = Camera::new();
+    if let Some(body) = focus {
+        camera.zoom = camera.distance / (space.system.bodies[body].scale * 6.0);
+    }
+    if let Some(zoom) = options.zoom {
+        camera.zoom = zoom;
+    }
+
+    let mut recorder = match options.format.as_str() {
+        "y4m" => {
+            std::fs::create_dir_all(&options.out_dir)
+                .map_err(|e| format!("no se pudo crear {}: {}", options.out_dir, e))?;
+            let path = Path::new(&options.out_dir).join("render.y4m");
+            Recorder::video(&path.to_string_lossy(), options.width, options.height, options.fps)
+        }
+        "ppm" => Recorder::images(&options.out_dir, "ppm"),
+        _ => Recorder::images(&options.out_dir, "png"),
+    }
+    .map_err(|e| format!("no se pudo preparar la salida en {}: {}", options.out_dir, e))?;
+
+    // Parpadeo de estrellas reproducible
+    let mut rng = StdRng::seed_from_u64(options.seed);
+
+    for frame in 0..options.frames {
+        let t = options.time + options.step * frame as f64;
+        let transforms = space.update(t);
+
+        if let Some(body) = focus {
+            camera.target = space.positions(&transforms)[body];
+        }
+
+        space.render(&mut framebuffer, &camera, &transforms, t as f32, &mut rng);
+
+        recorder
+            .record(&framebuffer)
+            .map_err(|e| format!("no se pudo guardar el frame {}: {}", frame, e))?;
+    }
+
+    let destination = recorder.destination().to_string();
+    let frames = recorder.frames();
+    recorder
+        .finish()
+        .map_err(|e| format!("no se pudo cerrar {}: {}", destination, e))?;
+    println!("{} frames → {}", frames, destination);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        Options::parse(&args)
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Ok(_) => panic!("{:?} debería fallar", args),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn defaults_and_values() {
+        let options = parse(&["--headless", "otro.toml", "--frames", "3", "--size", "320x200", "--time", "-5"]).unwrap();
+        assert_eq!(options.system_path, "otro.toml");
+        assert_eq!(options.frames, 3);
+        assert_eq!((options.width, options.height), (320, 200));
+        assert_eq!(options.time, -5.0);
+        assert_eq!(options.format, "png");
+    }
+
+    #[test]
+    fn missing_value() {
+        assert!(error(&["--frames"]).contains("falta el valor"));
+    }
+
+    #[test]
+    fn unknown_flag() {
+        assert!(error(&["--brillo", "2"]).contains("opción desconocida"));
+    }
+
+    #[test]
+    fn zero_size() {
+        assert!(parse(&["--size", "0x100"]).is_err());
+        assert!(parse(&["--size", "100x0"]).is_err());
+        assert!(parse(&["--size", "100"]).is_err());
+    }
+
+    #[test]
+    fn bad_numbers() {
+        for (flag, value) in [
+            ("--frames", "-1"),
+            ("--frames", "x"),
+            ("--time", "nan"),
+            ("--time", "inf"),
+            ("--step", "nan"),
+            ("--step", "-0.1"),
+            ("--fps", "0"),
+            ("--fps", "1.5"),
+            ("--seed", "-3"),
+            ("--zoom", "0"),
+            ("--zoom", "-2"),
+            ("--zoom", "inf"),
+            ("--exposure", "0"),
+            ("--exposure", "-1"),
+            ("--exposure", "nan"),
+        ] {
+            assert!(parse(&[flag, value]).is_err(), "{} {} debería fallar", flag, value);
+        }
+    }
+
+    #[test]
+    fn bad_names() {
+        assert!(parse(&["--format", "gif"]).is_err());
+        assert!(parse(&["--tone-mapping", "filmic"]).is_err());
+        assert!(parse(&["--bloom", "si"]).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// Escritura de imágenes sin depender de raylib (sirve también sin ventana)
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+// Bits de a poco, del menos significativo al más (el orden de deflate)
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // Los códigos de Huffman van con el bit más significativo primero
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+        }
+        self.out
+    }
+}
+
+// Tablas de deflate (RFC 1951, 3.2.5): base y bits extra de cada código de
+// largo (257..285) y de distancia (0..29)
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+const WINDOW: usize = 32768;
+const HASH_SIZE: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64; // candidatos que se prueban por posición
+
+// Símbolo literal/largo con el Huffman fijo de deflate
+fn write_symbol(w: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => w.write_code(0x30 + symbol, 8),
+        144..=255 => w.write_code(0x190 + symbol - 144, 9),
+        256..=279 => w.write_code(symbol - 256, 7),
+        _ => w.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(w: &mut BitWriter, length: usize, distance: usize) {
+    let l = LENGTH_BASE.iter().rposition(|&b| b as usize <= length).unwrap();
+    write_symbol(w, 257 + l as u32);
+    w.write((length - LENGTH_BASE[l] as usize) as u32, LENGTH_EXTRA[l] as u32);
+    let d = DISTANCE_BASE.iter().rposition(|&b| b as usize <= distance).unwrap();
+    w.write_code(d as u32, 5);
+    w.write((distance - DISTANCE_BASE[d] as usize) as u32, DISTANCE_EXTRA[d] as u32);
+}
+
+// Deflate en un solo bloque con Huffman fijo y LZ77 voraz (cadenas de hash
+// de 3 bytes). No llega a lo que comprime zlib, pero el fondo negro y las
+// zonas lisas de un render bajan a una fracción del tamaño sin comprimir.
+fn deflate_fixed(data: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter { out: Vec::new(), buffer: 0, count: 0 };
+    w.write(1, 1); // último bloque
+    w.write(1, 2); // tipo 01: Huffman fijo
+
+    let hash = |i: usize| {
+        ((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize) & (HASH_SIZE - 1)
+    };
+    let mut head = vec![usize::MAX; HASH_SIZE]; // última posición vista con cada hash
+    let mut prev = vec![usize::MAX; WINDOW]; // posición anterior con el mismo hash
+
+    let mut i = 0;
+    while i < data.len() {
+        // Coincidencia más larga hacia atrás dentro de la ventana
+        let (mut best_len, mut best_dist) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let max = (data.len() - i).min(MAX_MATCH);
+            let mut candidate = head[hash(i)];
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW && chain < MAX_CHAIN {
+                let len = data[candidate..]
+                    .iter()
+                    .zip(&data[i..i + max])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, i - candidate);
+                    if len == max {
+                        break;
+                    }
+                }
+                candidate = prev[candidate % WINDOW];
+                chain += 1;
+            }
+        }
+
+        let advance = if best_len >= MIN_MATCH {
+            write_match(&mut w, best_len, best_dist);
+            best_len
+        } else {
+            write_symbol(&mut w, data[i] as u32);
+            1
+        };
+        for j in i..i + advance {
+            if j + MIN_MATCH <= data.len() {
+                let h = hash(j);
+                prev[j % WINDOW] = head[h];
+                head[h] = j;
+            }
+        }
+        i += advance;
+    }
+
+    write_symbol(&mut w, 256); // fin de bloque
+    w.finish()
+}
+
+// Stream zlib: encabezado, deflate y checksum
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    out.extend_from_slice(&deflate_fixed(data));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    let mut crc_data = Vec::with_capacity(4 + data.len());
+    crc_data.extend_from_slice(kind);
+    crc_data.extend_from_slice(data);
+    out.write_all(&crc_data)?;
+    out.write_all(&crc32(&crc_data).to_be_bytes())
+}
+
+// `rgba` debe traer exactamente width * height píxeles, y la imagen no puede estar vacía
+fn check_size(width: u32, height: u32, rgba: &[u8]) -> io::Result<()> {
+    if width == 0 || height == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("imagen vacía ({}x{})", width, height),
+        ));
+    }
+    let expected = width as usize * height as usize * 4;
+    if rgba.len() != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("se esperaban {} bytes RGBA para {}x{}, llegaron {}", expected, width, height, rgba.len()),
+        ));
+    }
+    Ok(())
+}
+
+/// Escribe un PNG RGBA8 (`rgba` tiene width * height * 4 bytes, fila por fila)
+pub fn write_png(path: &str, width: u32, height: u32, rgba: &[u8]) -> io::Result<()> {
+    let png = encode_png(width, height, rgba)?;
+    let mut out = BufWriter::new(File::create(path)?);
+    out.write_all(&png)?;
+    out.flush()
+}
+
+// El archivo PNG completo en memoria
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> io::Result<Vec<u8>> {
+    check_size(width, height, rgba)?;
+    let mut out = Vec::new();
+    out.write_all(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'])?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bits, RGBA, sin entrelazado
+    write_chunk(&mut out, b"IHDR", &header)?;
+
+    // Cada fila lleva delante su tipo de filtro. Sub (1) guarda cada byte menos
+    // el del píxel de la izquierda: los degradados quedan en valores chicos y repetidos
+    let row_len = width as usize * 4;
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in rgba.chunks(row_len) {
+        raw.push(1);
+        raw.extend(row.iter().enumerate().map(|(i, &b)| match i.checked_sub(4) {
+            Some(left) => b.wrapping_sub(row[left]),
+            None => b,
+        }));
+    }
+    write_chunk(&mut out, b"IDAT", &zlib_compress(&raw))?;
+    write_chunk(&mut out, b"IEND", &[])?;
+
+    Ok(out)
+}
+
+/// Escribe un PPM binario (P6); el canal alfa se descarta
+pub fn write_ppm(path: &str, width: u32, height: u32, rgba: &[u8]) -> io::Result<()> {
+    check_size(width, height, rgba)?;
+    let mut out = BufWriter::new(File::create(path)?);
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    for pixel in rgba.chunks(4) {
+        out.write_all(&pixel[..3])?;
+    }
+    out.flush()
+}
+
+// Video Y4M (YUV4MPEG2) sin comprimir: lo leen ffmpeg, mpv y VLC.
+// Se usa YCbCr 4:4:4 con los coeficientes BT.601 de rango limitado.
+pub struct Y4mWriter {
+    out: BufWriter<File>,
+    width: u32,
+    height: u32,
+}
+
+impl Y4mWriter {
+    pub fn create(path: &str, width: u32, height: u32, fps: u32) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444", width, height, fps)?;
+        Ok(Y4mWriter { out, width, height })
+    }
+
+    /// Agrega un frame RGBA8 (`width * height * 4` bytes)
+    pub fn write_frame(&mut self, rgba: &[u8]) -> io::Result<()> {
+        let size = (self.width * self.height) as usize;
+        let mut planes = vec![0u8; size * 3];
+        let (y_plane, chroma) = planes.split_at_mut(size);
+        let (cb_plane, cr_plane) = chroma.split_at_mut(size);
+
+        for (i, pixel) in rgba.chunks(4).take(size).enumerate() {
+            let r = pixel[0] as f32 / 255.0;
+            let g = pixel[1] as f32 / 255.0;
+            let b = pixel[2] as f32 / 255.0;
+            y_plane[i] = (16.0 + 65.481 * r + 128.553 * g + 24.966 * b).round() as u8;
+            cb_plane[i] = (128.0 - 37.797 * r - 74.203 * g + 112.0 * b).round() as u8;
+            cr_plane[i] = (128.0 + 112.0 * r - 93.786 * g - 18.214 * b).round() as u8;
+        }
+
+        self.out.write_all(b"FRAME\n")?;
+        self.out.write_all(&planes)
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Inflate mínimo, escrito aparte del compresor: bloques guardados y de
+    // Huffman fijo (lo único que puede emitir `deflate_fixed`)
+    struct BitReader<'a> {
+        data: &'a [u8],
+        pos: usize, // en bits
+    }
+
+    impl BitReader<'_> {
+        fn bit(&mut self) -> u32 {
+            let byte = self.data[self.pos / 8];
+            let bit = (byte >> (self.pos % 8)) & 1;
+            self.pos += 1;
+            bit as u32
+        }
+
+        // Campo de `n` bits, el menos significativo primero
+        fn bits(&mut self, n: u32) -> u32 {
+            (0..n).fold(0, |acc, i| acc | (self.bit() << i))
+        }
+
+        // Código de Huffman: el más significativo primero
+        fn code(&mut self, n: u32) -> u32 {
+            (0..n).fold(0, |acc, _| (acc << 1) | self.bit())
+        }
+
+        fn symbol(&mut self) -> u32 {
+            let code = self.code(7);
+            if code <= 0x17 {
+                return 256 + code;
+            }
+            let code = (code << 1) | self.bit();
+            match code {
+                0x30..=0xBF => code - 0x30,
+                0xC0..=0xC7 => 280 + code - 0xC0,
+                _ => 144 + ((code << 1) | self.bit()) - 0x190,
+            }
+        }
+    }
+
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut r = BitReader { data, pos: 0 };
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            let last = r.bits(1);
+            match r.bits(2) {
+                0 => {
+                    r.pos = r.pos.div_ceil(8) * 8;
+                    let len = r.bits(16) as usize;
+                    assert_eq!(r.bits(16) as usize, !len & 0xFFFF);
+                    let start = r.pos / 8;
+                    out.extend_from_slice(&data[start..start + len]);
+                    r.pos += len * 8;
+                }
+                1 => loop {
+                    let symbol = r.symbol();
+                    match symbol {
+                        0..=255 => out.push(symbol as u8),
+                        256 => break,
+                        _ => {
+                            let l = (symbol - 257) as usize;
+                            let length = LENGTH_BASE[l] as usize + r.bits(LENGTH_EXTRA[l] as u32) as usize;
+                            let d = r.code(5) as usize;
+                            let distance = DISTANCE_BASE[d] as usize + r.bits(DISTANCE_EXTRA[d] as u32) as usize;
+                            assert!(distance <= out.len(), "distancia fuera de lo ya escrito");
+                            for _ in 0..length {
+                                out.push(out[out.len() - distance]);
+                            }
+                        }
+                    }
+                },
+                kind => panic!("tipo de bloque inesperado {}", kind),
+            }
+            if last == 1 {
+                return out;
+            }
+        }
+    }
+
+    // Decodifica el PNG que escribe `encode_png` y devuelve los bytes RGBA
+    fn decode_png(png: &[u8]) -> (u32, u32, Vec<u8>) {
+        assert_eq!(&png[..8], &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']);
+        let (mut width, mut height, mut idat) = (0, 0, Vec::new());
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let body = &png[pos + 4..pos + 8 + len];
+            let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+            assert_eq!(crc32(body), crc, "CRC del chunk");
+            match &body[..4] {
+                b"IHDR" => {
+                    width = u32::from_be_bytes(body[4..8].try_into().unwrap());
+                    height = u32::from_be_bytes(body[8..12].try_into().unwrap());
+                }
+                b"IDAT" => idat.extend_from_slice(&body[4..]),
+                _ => {}
+            }
+            pos += 12 + len;
+        }
+
+        assert_eq!(((idat[0] as u32) << 8 | idat[1] as u32) % 31, 0, "encabezado zlib");
+        let raw = inflate(&idat[2..idat.len() - 4]);
+        let adler = u32::from_be_bytes(idat[idat.len() - 4..].try_into().unwrap());
+        assert_eq!(adler32(&raw), adler);
+
+        let row_len = width as usize * 4;
+        let mut rgba = Vec::new();
+        for line in raw.chunks(row_len + 1) {
+            assert_eq!(line[0], 1, "filtro Sub");
+            let start = rgba.len();
+            for (i, &b) in line[1..].iter().enumerate() {
+                let left = if i >= 4 { rgba[start + i - 4] } else { 0 };
+                rgba.push(b.wrapping_add(left));
+            }
+        }
+        (width, height, rgba)
+    }
+
+    fn round_trip(width: u32, height: u32, rgba: &[u8]) {
+        let png = encode_png(width, height, rgba).unwrap();
+        assert_eq!(decode_png(&png), (width, height, rgba.to_vec()));
+    }
+
+    #[test]
+    fn png_flat() {
+        let rgba = [10, 20, 30, 255].repeat(64 * 48);
+        round_trip(64, 48, &rgba);
+    }
+
+    #[test]
+    fn png_gradient() {
+        let (w, h) = (300, 40);
+        let rgba: Vec<u8> = (0..w * h)
+            .flat_map(|i| {
+                let (x, y) = (i % w, i / w);
+                [x as u8, (x + y) as u8, (y * 6) as u8, 255]
+            })
+            .collect();
+        round_trip(w, h, &rgba);
+    }
+
+    #[test]
+    fn png_noise() {
+        // xorshift: pocas repeticiones, ejercita literales y coincidencias cortas
+        let mut state = 0x1234_5678u32;
+        let rgba: Vec<u8> = (0..97 * 31 * 4)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state % 5) as u8 * 51
+            })
+            .collect();
+        round_trip(97, 31, &rgba);
+    }
+
+    #[test]
+    fn png_single_pixel() {
+        round_trip(1, 1, &[1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn png_rejects_bad_sizes() {
+        assert!(encode_png(0, 10, &[]).is_err());
+        assert!(encode_png(2, 2, &[0; 12]).is_err());
+    }
+}
@@ -1,34 +1,71 @@
 mod framebuffer;
+mod image_io;
 mod triangle;
 mod vertex;
 mod fragment;
 mod shaders;
+mod texture;
+mod tonemap;
+mod bloom;
+mod noise;
+mod shadow;
 mod obj;
 mod matrix;
-
-
-use framebuffer::Framebuffer;
-use shaders::vertex_shader;
+mod clipping;
+mod raster;
+mod orbit;
+mod clock;
+mod scene;
+mod scene_graph;
+mod headless;
+mod recorder;
+#[cfg(test)]
+mod golden_tests;
+
+
+use framebuffer::{BlendState, Framebuffer};
 use obj::Obj;
+use scene::SystemDesc;
+use scene_graph::SceneNode;
+use shadow::{Occluder, ShadowCaster};
+use clock::SimClock;
+use recorder::{next_free_path, Recorder, RECORD_FPS};
 use raylib::prelude::*;
-use std::time::Duration;
-use std::thread;
+use shaders::{Material, PlanetShader, ShaderRegistry};
 use std::f32::consts::PI;
-use crate::matrix::new_matrix4;
-use rand::Rng;
+use std::sync::Arc;
+use crate::matrix::{
+    create_normal_matrix, create_projection_matrix, create_view_matrix, create_viewport_matrix,
+    multiply_matrix_vector4, new_matrix4,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
+#[derive(Clone)]
 pub struct Uniforms {
     pub model_matrix: Matrix,
-    pub shader_type: u32,
+    pub view_matrix: Matrix,
+    pub projection_matrix: Matrix,
+    pub viewport_matrix: Matrix,
+    pub normal_matrix: Matrix,
+    pub light_position: Vector3, // posición del sol en mundo
+    pub light_radius: f32,       // radio del sol en mundo (ancho de las penumbras)
+    pub camera_position: Vector3, // ojo de la cámara en mundo
+    pub shader: Arc<dyn PlanetShader>, // material del cuerpo
     pub base_color1: Vector3,
     pub base_color2: Vector3,
     pub light_intensity: f32,
     pub ambient_strength: f32,
     pub emission_strength: f32,
+    pub time: f32, // tiempo de simulación, para los shaders animados
+    pub seed: u32, // semilla del cuerpo
+    pub blend: BlendState, // modo de mezcla y escritura de profundidad
+    pub material: Material,  // brillo especular del cuerpo
+    pub occluders: Vec<Occluder>, // lo que puede taparle el sol (anillos, otros cuerpos)
 }
 
 // Combina traslación + escala + rotación
-fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3) -> Matrix {
+pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3) -> Matrix {
     let (sin_x, cos_x) = rotation.x.sin_cos();
     let (sin_y, cos_y) = rotation.y.sin_cos();
     let (sin_z, cos_z) = rotation.z.sin_cos();
@@ -71,64 +108,56 @@ fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3) -> M
     scale_matrix * rotation * translation_matrix
 }
 
-// Pipeline: vertex → triángulos → fragments → framebuffer (con z-buffer)
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, obj: &Obj) {
-    let mut transformed = Vec::with_capacity(obj.vertices.len());
-    for v in &obj.vertices {
-        transformed.push(vertex_shader(v, uniforms));
-    }
-
-    let mut fragments = Vec::new();
-    for face in obj.indices.chunks(3) {
-        let v0 = &transformed[face[0] as usize];
-        let v1 = &transformed[face[1] as usize];
-        let v2 = &transformed[face[2] as usize];
-        fragments.extend(triangle::triangle(v0, v1, v2));
-    }
-
-    for frag in fragments {
-        framebuffer.point(
-            frag.position.x as i32,
-            frag.position.y as i32,
-            frag.color,
-            frag.depth, // 👈 Aquí usamos la profundidad
-        );
-    }
-}
-
-
-// Cámara libre sobre el plano eclíptico con zoom
+// Cámara orbital que mira a un punto del plano eclíptico, con zoom
 struct Camera {
-    pub pos: Vector3,   // posición en espacio
-    pub zoom: f32,      // factor de zoom
+    pub target: Vector3, // punto al que mira la cámara
+    pub distance: f32,   // distancia base al objetivo
+    pub pitch: f32,      // inclinación sobre la eclíptica (radianes)
+    pub zoom: f32,       // factor de zoom (divide la distancia)
 }
 
 impl Camera {
     fn new() -> Self {
         Self {
-            pos: Vector3::new(0.0, 0.0, 0.0),
+            target: Vector3::new(0.0, 0.0, 0.0),
+            distance: 2600.0,
+            pitch: 0.45,
             zoom: 1.0,
         }
     }
 
+    // Posición del ojo, detrás y por encima del objetivo
+    fn eye(&self) -> Vector3 {
+        let d = self.distance / self.zoom;
+        Vector3::new(
+            self.target.x,
+            self.target.y + d * self.pitch.sin(),
+            self.target.z - d * self.pitch.cos(),
+        )
+    }
+
+    fn view_matrix(&self) -> Matrix {
+        create_view_matrix(self.eye(), self.target, Vector3::new(0.0, 1.0, 0.0))
+    }
+
     fn update(&mut self, window: &RaylibHandle) {
-        let move_speed = 6.0;
+        let move_speed = 6.0 / self.zoom;
         let zoom_speed = 0.02;
 
-        // Movimiento horizontal (izquierda / derecha)
+        // Movimiento lateral (izquierda / derecha)
         if window.is_key_down(KeyboardKey::KEY_A) {
-            self.pos.x -= move_speed;
+            self.target.x += move_speed;
         }
         if window.is_key_down(KeyboardKey::KEY_D) {
-            self.pos.x += move_speed;
+            self.target.x -= move_speed;
         }
 
-        // Movimiento vertical (arriba / abajo)
+        // Movimiento sobre la eclíptica (adelante / atrás)
         if window.is_key_down(KeyboardKey::KEY_W) {
-            self.pos.y -= move_speed;
+            self.target.z += move_speed;
         }
         if window.is_key_down(KeyboardKey::KEY_S) {
-            self.pos.y += move_speed;
+            self.target.z -= move_speed;
         }
 
         // Zoom con flechas arriba/abajo
@@ -140,368 +169,468 @@ impl Camera {
         }
 
         // Limita el zoom
-        self.zoom = self.zoom.clamp(0.3, 3.0);
+        self.zoom = self.zoom.clamp(0.3, 10.0);
     }
 }
 
 
 struct Star {
-    pos: Vector3,   // posición espacial (x, y, z)
+    dir: Vector3,   // dirección en el cielo (las estrellas están "en el infinito")
     color: Vector3, // color de la estrella
 }
 
-fn draw_skybox(framebuffer: &mut Framebuffer, stars: &Vec<Star>, camera: &Camera) {
-    let width = framebuffer.width as f32;
-    let height = framebuffer.height as f32;
-
+fn draw_skybox(
+    framebuffer: &mut Framebuffer,
+    stars: &[Star],
+    view_matrix: &Matrix,
+    projection_matrix: &Matrix,
+    viewport_matrix: &Matrix,
+    rng: &mut impl Rng,
+) {
     for star in stars {
-        // posición relativa a la cámara (solo movimiento, sin zoom)
-        let rel = star.pos - camera.pos;
+        // w = 0: solo rota con la cámara, no se traslada
+        let dir = Vector4::new(star.dir.x, star.dir.y, star.dir.z, 0.0);
+        let view = multiply_matrix_vector4(view_matrix, &dir);
+        if view.z >= 0.0 {
+            continue; // detrás de la cámara
+        }
 
-        // proyección sin aplicar zoom
-        let x_screen = width / 2.0 + rel.x;
-        let y_screen = height / 2.0 + rel.y;
+        let clip = multiply_matrix_vector4(projection_matrix, &view);
+        let ndc = Vector4::new(clip.x / clip.w, clip.y / clip.w, 1.0, 1.0);
+        let screen = multiply_matrix_vector4(viewport_matrix, &ndc);
 
-        if x_screen >= 0.0 && x_screen < width && y_screen >= 0.0 && y_screen < height {
-            framebuffer.point(x_screen as i32, y_screen as i32, star.color, 10_000.0);
+        let x_screen = screen.x as i32;
+        let y_screen = screen.y as i32;
 
-            // opcional: algunas estrellas más brillantes
-            if rand::random::<f32>() < 0.1 {
-                framebuffer.point(x_screen as i32 + 1, y_screen as i32, star.color, 10_000.0);
-                framebuffer.point(x_screen as i32, y_screen as i32 + 1, star.color, 10_000.0);
-            }
+        // profundidad 1.0 = plano lejano, cualquier planeta queda delante
+        framebuffer.point(x_screen, y_screen, star.color, 1.0);
+
+        // opcional: algunas estrellas más brillantes
+        if rng.gen_bool(0.1) {
+            framebuffer.point(x_screen + 1, y_screen, star.color, 1.0);
+            framebuffer.point(x_screen, y_screen + 1, star.color, 1.0);
         }
     }
 }
 
 
+// Espacio oscuro (luz lineal: en pantalla queda cerca de (0.01, 0.01, 0.03))
+const BACKGROUND_COLOR: Vector3 = Vector3::new(0.0008, 0.0008, 0.0023);
+
+// Todo lo necesario para dibujar el sistema; se usa igual con ventana o sin ella
+struct Space {
+    system: SystemDesc,
+    shaders: ShaderRegistry,
+    meshes: Vec<Obj>,
+    scene: SceneNode,
+    stars: Vec<Star>,
+    casters: Vec<Option<ShadowCaster>>, // forma de sombra de cada cuerpo
+    sun: usize,
+    sun_radius: f32, // radio de la malla del sol (espacio de objeto)
+    projection_matrix: Matrix,
+    viewport_matrix: Matrix,
+}
 
+impl Space {
+    /// Carga el sistema y sus mallas para un framebuffer de `width` x `height`.
+    /// `seed` fija el cielo de estrellas: misma semilla, mismo cielo.
+    fn load(system_path: &str, width: u32, height: u32, seed: u64) -> Result<Self, String> {
+        let shaders = ShaderRegistry::builtin();
+        let system = SystemDesc::load(system_path, &shaders)?;
+
+        // Modelos (uno por archivo distinto del sistema)
+        let meshes = system
+            .mesh_paths
+            .iter()
+            .map(|path| Obj::load(path).map_err(|e| format!("no se pudo cargar {}: {:?}", path, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Proyección y viewport (fijos mientras no cambie el tamaño)
+        let fov_y = 60.0_f32.to_radians();
+        let aspect = width as f32 / height as f32;
+        let projection_matrix = create_projection_matrix(fov_y, aspect, 10.0, 20_000.0);
+        let viewport_matrix = create_viewport_matrix(0.0, 0.0, width as f32, height as f32);
+
+        // La luz sale de la primera estrella del sistema
+        let sun = system
+            .bodies
+            .iter()
+            .position(|b| b.shader.is_emissive())
+            .unwrap_or(0);
+
+        let sun_radius = ShadowCaster::bounding_radius(&meshes[system.bodies[sun].mesh]);
+
+        // Formas de sombra, medidas sobre la malla de cada cuerpo
+        let casters = system
+            .bodies
+            .iter()
+            .map(|b| b.shader.shadow_shape().map(|shape| ShadowCaster::from_mesh(shape, &meshes[b.mesh])))
+            .collect();
+
+        // Árbol de escena: cada cuerpo cuelga del marco orbital de su padre
+        let scene = SceneNode::from_system(&system);
+
+        // Estrellas: direcciones aleatorias sobre la esfera celeste
+        let mut rng = StdRng::seed_from_u64(seed);
+        let num_stars = 2000;
+        let stars = (0..num_stars)
+            .map(|_| {
+                let z: f32 = rng.gen_range(-1.0..1.0);
+                let phi: f32 = rng.gen_range(0.0..2.0 * PI);
+                let r = (1.0 - z * z).sqrt();
+                let brightness: f32 = rng.gen_range(0.5..1.0);
+                let color = Vector3::new(
+                    brightness,
+                    brightness * rng.gen_range(0.7..1.0),
+                    brightness * rng.gen_range(0.8..1.0),
+                );
+                Star { dir: Vector3::new(r * phi.cos(), z, r * phi.sin()), color }
+            })
+            .collect();
+
+        Ok(Space {
+            system,
+            shaders,
+            meshes,
+            scene,
+            stars,
+            casters,
+            sun,
+            sun_radius,
+            projection_matrix,
+            viewport_matrix,
+        })
+    }
 
+    // Coloca el árbol en el tiempo t y devuelve la matriz de mundo de cada cuerpo
+    fn update(&mut self, t: f64) -> Vec<(usize, Matrix)> {
+        self.scene.update(&self.system, t);
+        self.scene.body_transforms()
+    }
 
-fn main() {
-    let screen_width = 800;
-    let screen_height = 600;
-    let center_x = (screen_width / 2) as f32;
-    let center_y = (screen_height / 2) as f32;
-
-    let (mut window, thread) = raylib::init()
-        .size(screen_width, screen_height)
-        .title("Proyecto 3: Space Travel")
-        .build();
-
-    let mut framebuffer = Framebuffer::new(800, 600);
-    framebuffer.set_background_color(Vector3::new(0.01, 0.01, 0.03)); // espacio oscuro
+    // Posición de mundo de cada cuerpo, indexada por cuerpo
+    fn positions(&self, transforms: &[(usize, Matrix)]) -> Vec<Vector3> {
+        let mut positions = vec![Vector3::zero(); self.system.bodies.len()];
+        for (body, world) in transforms {
+            positions[*body] = Vector3::new(world.m12, world.m13, world.m14);
+        }
+        positions
+    }
 
-    // Modelos
-    let sphere = Obj::load("src/planeta.obj").expect("No se pudo cargar planeta.obj");
-    let ring   = Obj::load("src/ring.obj").expect("No se pudo cargar ring.obj");
+    /// Dibuja un frame completo: cielo, cuerpos y sus atmósferas.
+    /// `time` es el mismo t de `update`; `rng` decide qué estrellas brillan más en este frame.
+    fn render(
+        &self,
+        framebuffer: &mut Framebuffer,
+        camera: &Camera,
+        transforms: &[(usize, Matrix)],
+        time: f32,
+        rng: &mut impl Rng,
+    ) {
+        let light_position = self.positions(transforms)[self.sun];
+        let light_radius = transforms
+            .iter()
+            .find(|(i, _)| *i == self.sun)
+            .map_or(0.0, |(_, m)| Vector3::new(m.m0, m.m1, m.m2).length() * self.sun_radius);
+        let view_matrix = &camera.view_matrix();
+        let camera_position = camera.eye();
 
-    // Centro del sistema y plano eclíptico
-    let sun_pos = Vector3::new(center_x, center_y, 600.0);
-    let ecliptic_y = sun_pos.y;
+        framebuffer.clear();
+        draw_skybox(
+            framebuffer,
+            &self.stars,
+            view_matrix,
+            &self.projection_matrix,
+            &self.viewport_matrix,
+            rng,
+        );
 
-    // Colores
-    let star_color1  = Vector3::new(1.00, 0.90, 0.45);
-    let star_color2  = Vector3::new(0.25, 0.18, 0.08);
+        // Pipeline: vertex → recorte → binning por tiles → raster + fragment shader en paralelo
+        let mut frame_uniforms = Vec::with_capacity(transforms.len());
+        let mut triangles = Vec::new();
+
+        // Sombras: cada cuerpo se ubica en el mundo y recibe las formas que lo pueden tapar
+        let placed: Vec<Option<Occluder>> = transforms
+            .iter()
+            .map(|(i, model)| {
+                let shader = &self.system.bodies[*i].shader;
+                self.casters[*i].map(|caster| caster.place(model, shader))
+            })
+            .collect();
+        let occluders = shadow::occluders_for(&placed);
+
+        for ((i, model), occluders) in transforms.iter().zip(occluders) {
+            let body = &self.system.bodies[*i];
+            let model = *model;
+            let uniforms = Uniforms {
+                model_matrix: model,
+                normal_matrix: create_normal_matrix(&model),
+                view_matrix: *view_matrix,
+                projection_matrix: self.projection_matrix,
+                viewport_matrix: self.viewport_matrix,
+                light_position,
+                light_radius,
+                camera_position,
+                shader: body.shader.clone(),
+                base_color1: body.color1,
+                base_color2: body.color2,
+                light_intensity: self.system.light_intensity,
+                ambient_strength: self.system.ambient_strength,
+                emission_strength: self.system.emission_strength,
+                time,
+                seed: body.seed,
+                blend: body.blend,
+                material: body.material,
+                occluders,
+            };
+            let mesh = &self.meshes[body.mesh];
+            raster::assemble(&mut triangles, frame_uniforms.len(), &uniforms, mesh);
+
+            // La atmósfera es la misma malla otra vez con su propio shader
+            // (transparente, así que se mezcla sobre el planeta y el espacio)
+            let shell = body.atmosphere.as_ref().map(|atmosphere| Uniforms {
+                shader: atmosphere.shader.clone(),
+                base_color1: atmosphere.color,
+                base_color2: atmosphere.sunset_color,
+                blend: BlendState::new(atmosphere.shader.blend_mode()),
+                ..uniforms.clone()
+            });
+            frame_uniforms.push(uniforms);
+            if let Some(shell) = shell {
+                raster::assemble(&mut triangles, frame_uniforms.len(), &shell, mesh);
+                frame_uniforms.push(shell);
+            }
+        }
 
-    let rocky_color1 = Vector3::new(0.22, 0.55, 0.85);
-    let rocky_color2 = Vector3::new(0.05, 0.20, 0.10);
+        raster::rasterize(framebuffer, &triangles, &frame_uniforms);
+    }
+}
 
-    let gas_color1   = Vector3::new(0.92, 0.74, 0.46);
-    let gas_color2   = Vector3::new(0.62, 0.52, 0.34);
+fn stop_recording(recorder: Recorder) {
+    let destination = recorder.destination().to_string();
+    let frames = recorder.frames();
+    match recorder.finish() {
+        Ok(()) => println!("Grabación terminada: {} frames en {}", frames, destination),
+        Err(e) => eprintln!("No se pudo cerrar {}: {}", destination, e),
+    }
+}
 
-    let lava_color1  = Vector3::new(0.75, 0.15, 0.05);
-    let lava_color2  = Vector3::new(0.10, 0.02, 0.01);
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    let cyber_color1 = Vector3::new(0.15, 0.18, 0.22);
-    let cyber_color2 = Vector3::new(0.00, 0.75, 1.00);
+    // Sin ventana: renderiza frames a disco y termina
+    if args.iter().any(|a| a == "--headless") {
+        if let Err(e) = headless::run(&args) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let ring_color_inner = Vector3::new(0.65, 0.60, 0.50);
-    let ring_color_outer = Vector3::new(0.85, 0.80, 0.70);
+    let screen_width = 800;
+    let screen_height = 600;
 
-    // Iluminación
-    let light_intensity: f32   = 1.0;
-    let ambient_strength: f32  = 0.18;
-    let emission_strength: f32 = 1.2;
+    let mut framebuffer = Framebuffer::new(800, 600);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+
+    // Sistema a cargar: primer argumento o el sistema por defecto
+    let system_path = args
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "src/sistema.toml".to_string());
+    let mut space = Space::load(&system_path, framebuffer.width, framebuffer.height, rand::random())
+        .unwrap_or_else(|e| panic!("No se pudo cargar el sistema: {}", e));
+    framebuffer.exposure = space.system.exposure;
+    framebuffer.tone_mapping = space.system.tone_mapping;
+    framebuffer.bloom = space.system.bloom;
 
-    // Radios de órbita
-    let rocky_orbit_radius = 320.0;   // cerca del sol
-    let gas_orbit_radius   = 700.0;   // bastante más afuera
-    let cyber_orbit_radius = 1100.0;  // lejos
-    let lava_orbit_radius  = 1500.0;  // el más externo
+    let (mut window, thread) = raylib::init()
+        .size(screen_width, screen_height)
+        .title("Proyecto 3: Space Travel")
+        .build();
+    window.set_target_fps(60);
+
+    // Textura persistente: cada frame solo se actualizan sus píxeles
+    let mut texture = {
+        let image = Image::gen_image_color(
+            framebuffer.width as i32,
+            framebuffer.height as i32,
+            Color::BLACK,
+        );
+        window
+            .load_texture_from_image(&thread, &image)
+            .expect("No se pudo crear textura para el framebuffer")
+    };
+
+    // Teclas de warp: 1..9 en el orden del archivo
+    let warp_keys = [
+        KeyboardKey::KEY_ONE,
+        KeyboardKey::KEY_TWO,
+        KeyboardKey::KEY_THREE,
+        KeyboardKey::KEY_FOUR,
+        KeyboardKey::KEY_FIVE,
+        KeyboardKey::KEY_SIX,
+        KeyboardKey::KEY_SEVEN,
+        KeyboardKey::KEY_EIGHT,
+        KeyboardKey::KEY_NINE,
+    ];
+    let warp_bodies = space.system.warp_bodies();
 
     // Cámara
     let mut camera = Camera::new();
-    let mut t: f32 = 0.0;
 
-    // Estrellas
+    // Reloj de simulación: todo el movimiento orbital y de rotación depende de él
+    let mut clock = SimClock::new();
+
+    // Parpadeo de las estrellas
     let mut rng = rand::thread_rng();
-    let mut stars = Vec::new();
-    let num_stars = 2000;
-
-    for _ in 0..num_stars {
-        let x = rng.gen_range(-3000.0..3000.0);
-        let y = rng.gen_range(-1500.0..1500.0);
-        let z = rng.gen_range(2000.0..9000.0);
-        let brightness: f32 = rng.gen_range(0.5..1.0);
-        let color = Vector3::new(
-            brightness,
-            brightness * rng.gen_range(0.7..1.0),
-            brightness * rng.gen_range(0.8..1.0),
-        );
-        stars.push(Star { pos: Vector3::new(x, y, z), color });
-    }
+
+    // Grabación en curso (V: video Y4M, I: secuencia de PNG)
+    let mut recorder: Option<Recorder> = None;
 
     let mut warp_target: Option<Vector3> = None;
 
-    let mut moon_angle: f32 = 0.0;
-let moon_distance: f32 = 120.0;      // distancia desde el planeta
-let moon_scale_factor: f32 = 0.35; 
+    // Panel con los shaders registrados (L)
+    let mut show_shaders = false;
 
     while !window.window_should_close() {
-        t += 0.01;
+        // Controles de tiempo
+        if window.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            clock.toggle_pause();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_EQUAL) {
+            clock.speed_up();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_MINUS) {
+            clock.slow_down();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_R) {
+            clock.reverse();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_PERIOD) {
+            clock.step(1);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_COMMA) {
+            clock.step(-1);
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_L) {
+            show_shaders = !show_shaders;
+        }
+
+        // Presentación: operador de tone mapping y exposición (en pasos de medio stop)
+        if window.is_key_pressed(KeyboardKey::KEY_T) {
+            framebuffer.tone_mapping = framebuffer.tone_mapping.next();
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_RIGHT_BRACKET) {
+            framebuffer.exposure *= std::f32::consts::SQRT_2;
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_LEFT_BRACKET) {
+            framebuffer.exposure /= std::f32::consts::SQRT_2;
+        }
+        if window.is_key_pressed(KeyboardKey::KEY_B) {
+            framebuffer.bloom.enabled = !framebuffer.bloom.enabled;
+        }
+
+        // Grabación: al empezar o terminar se anuncia por consola
+        let toggle_video = window.is_key_pressed(KeyboardKey::KEY_V);
+        let toggle_images = window.is_key_pressed(KeyboardKey::KEY_I);
+        if toggle_video || toggle_images {
+            match recorder.take() {
+                Some(active) => stop_recording(active),
+                None => {
+                    let started = if toggle_video {
+                        Recorder::video(
+                            &next_free_path("recording", ".y4m"),
+                            framebuffer.width,
+                            framebuffer.height,
+                            RECORD_FPS,
+                        )
+                    } else {
+                        Recorder::images(&next_free_path("recording", ""), "png")
+                    };
+                    match started {
+                        Ok(active) => {
+                            println!("Grabando en {}", active.destination());
+                            recorder = Some(active);
+                        }
+                        Err(e) => eprintln!("No se pudo iniciar la grabación: {}", e),
+                    }
+                }
+            }
+        }
+
+        // Mientras se graba, cada frame avanza un paso fijo: el video queda
+        // fluido aunque el render vaya más lento que el tiempo real
+        let real_dt = if recorder.is_some() {
+            1.0 / RECORD_FPS as f32
+        } else {
+            window.get_frame_time()
+        };
+        clock.tick(real_dt);
+        let t = clock.time;
 
         // actualizar cámara con teclas (mov/zoom)
         camera.update(&window);
 
-        let base_z = sun_pos.z;
-
         // =========================
-        // POSICIONES MUNDO (sin cámara)
+        // POSICIONES MUNDO
         // =========================
-
-        // Sol (fijo)
-        let sun_world = sun_pos;
-
-        // Rocoso
-        let rocky_angle = t * 0.9;
-        let rocky_world = Vector3::new(
-            sun_pos.x + rocky_orbit_radius * rocky_angle.cos(),
-            ecliptic_y,
-            base_z + rocky_orbit_radius * rocky_angle.sin(),
-        );
-
-        // Gaseoso
-        let gas_angle = t * 0.55;
-        let gas_world = Vector3::new(
-            sun_pos.x + gas_orbit_radius * gas_angle.cos(),
-            ecliptic_y,
-            base_z + gas_orbit_radius * gas_angle.sin(),
-        );
-
-        // Cibernético
-        let cyber_angle = t * 0.42;
-        let cyber_world = Vector3::new(
-            sun_pos.x + cyber_orbit_radius * cyber_angle.cos(),
-            ecliptic_y,
-            base_z + cyber_orbit_radius * cyber_angle.sin(),
-        );
-
-        // Lava
-        let lava_angle = t * 0.32;
-        let lava_world = Vector3::new(
-            sun_pos.x + lava_orbit_radius * lava_angle.cos(),
-            ecliptic_y,
-            base_z + lava_orbit_radius * lava_angle.sin(),
-        );
+        let transforms = space.update(t);
+        let positions = space.positions(&transforms);
 
         // =========================
         // INSTANT WARP (usa POS MUNDO)
         // =========================
-// --- WARP SUAVE ---
-if window.is_key_pressed(KeyboardKey::KEY_ONE) {
-    warp_target = Some(Vector3::new(sun_world.x - center_x, sun_world.y - center_y, 0.0));
-    camera.zoom = 1.0;
-}
-if window.is_key_pressed(KeyboardKey::KEY_TWO) {
-    warp_target = Some(Vector3::new(rocky_world.x - center_x, rocky_world.y - center_y, 0.0));
-    camera.zoom = 1.0;
-}
-if window.is_key_pressed(KeyboardKey::KEY_THREE) {
-    warp_target = Some(Vector3::new(gas_world.x - center_x, gas_world.y - center_y, 0.0));
-    camera.zoom = 1.0;
-}
-if window.is_key_pressed(KeyboardKey::KEY_FOUR) {
-    warp_target = Some(Vector3::new(cyber_world.x - center_x, cyber_world.y - center_y, 0.0));
-    camera.zoom = 1.0;
-}
-if window.is_key_pressed(KeyboardKey::KEY_FIVE) {
-    warp_target = Some(Vector3::new(lava_world.x - center_x, lava_world.y - center_y, 0.0));
-    camera.zoom = 1.0;
-}
+        // --- WARP SUAVE ---
+        // El zoom se ajusta para que el cuerpo ocupe buena parte de la pantalla
+        for (key, &body) in warp_keys.iter().zip(&warp_bodies) {
+            if window.is_key_pressed(*key) {
+                warp_target = Some(positions[body]);
+                camera.zoom = camera.distance / (space.system.bodies[body].scale * 6.0);
+            }
+        }
 
-// Si hay warp pendiente → animar suavemente
-if let Some(target) = warp_target {
-    let dx = target.x - camera.pos.x;
-    let dy = target.y - camera.pos.y;
-    let dist = (dx * dx + dy * dy).sqrt();
-
-    // Movimiento lineal interpolado
-    camera.pos.x += dx * 0.08;
-    camera.pos.y += dy * 0.08;
-
-    // Cuando llega cerca, detener warp
-    if dist < 2.0 {
-        camera.pos.x = target.x;
-        camera.pos.y = target.y;
-        warp_target = None;
-    }
-}
+        // Si hay warp pendiente → animar suavemente
+        if let Some(target) = warp_target {
+            let delta = target - camera.target;
+            let dist = delta.length();
+
+            // Movimiento lineal interpolado
+            camera.target += delta * 0.08;
+
+            // Cuando llega cerca, detener warp
+            if dist < 2.0 {
+                camera.target = target;
+                warp_target = None;
+            }
+        }
 
         // =========================
         // RENDER
         // =========================
 
-        framebuffer.clear();
-        draw_skybox(&mut framebuffer, &stars, &camera);
-
-        // ☀️ Sol
-        let sun_screen = Vector3::new(
-            (sun_world.x - camera.pos.x) * camera.zoom,
-            (sun_world.y - camera.pos.y) * camera.zoom,
-            sun_world.z - camera.pos.z,
-        );
-        let sun_rot = Vector3::new(0.0, t * 0.4, 0.0);
-        let sun_uniforms = Uniforms {
-            model_matrix: create_model_matrix(sun_screen, 150.0 * camera.zoom, sun_rot),
-            shader_type: 0,
-            base_color1: star_color1,
-            base_color2: star_color2,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &sun_uniforms, &sphere);
-
-        // 🌎 Rocoso
-        let rocky_screen = Vector3::new(
-            (rocky_world.x - camera.pos.x) * camera.zoom,
-            (rocky_world.y - camera.pos.y) * camera.zoom,
-            rocky_world.z - camera.pos.z,
-        );
-        let rocky_rot = Vector3::new(0.0, t * 2.0, 0.0);
-        let rocky_uniforms = Uniforms {
-            model_matrix: create_model_matrix(rocky_screen, 70.0 * camera.zoom, rocky_rot),
-            shader_type: 1,
-            base_color1: rocky_color1,
-            base_color2: rocky_color2,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &rocky_uniforms, &sphere);
-
-        // 🌕 Luna del planeta rocoso
-moon_angle += 0.02; // velocidad de órbita de la luna
-
-// Posición de la luna girando alrededor del planeta rocoso
-let moon_world_pos = Vector3::new(
-    rocky_world.x + moon_distance * moon_angle.cos(),
-    rocky_world.y,
-    rocky_world.z + moon_distance * moon_angle.sin(),
-);
-
-// Convertir posición de mundo a pantalla según la cámara
-let moon_screen_pos = Vector3::new(
-    (moon_world_pos.x - camera.pos.x) * camera.zoom,
-    (moon_world_pos.y - camera.pos.y) * camera.zoom,
-    moon_world_pos.z - camera.pos.z,
-);
-
-// Rotación y escala de la luna
-let moon_rotation = Vector3::new(0.0, t * 3.0, 0.0);
-let moon_scale = 70.0 * moon_scale_factor * camera.zoom; // usa el tamaño del planeta base (70.0)
-
-// Configuración del shader
-let moon_uniforms = Uniforms {
-    model_matrix: create_model_matrix(moon_screen_pos, moon_scale, moon_rotation),
-    shader_type: 1, // mismo shader que el rocoso
-    base_color1: Vector3::new(0.7, 0.7, 0.7), // gris claro
-    base_color2: Vector3::new(0.3, 0.3, 0.3), // gris oscuro
-    light_intensity,
-    ambient_strength,
-    emission_strength,
-};
-
-// Renderizar luna
-render(&mut framebuffer, &moon_uniforms, &sphere);
-
-        // ☁️ Gaseoso
-        let gas_screen = Vector3::new(
-            (gas_world.x - camera.pos.x) * camera.zoom,
-            (gas_world.y - camera.pos.y) * camera.zoom,
-            gas_world.z - camera.pos.z,
-        );
-        let gas_rot = Vector3::new(t * 1.8, t * 1.2, t * 0.7);
-        let gas_uniforms = Uniforms {
-            model_matrix: create_model_matrix(gas_screen, 95.0 * camera.zoom, gas_rot),
-            shader_type: 2,
-            base_color1: gas_color1,
-            base_color2: gas_color2,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &gas_uniforms, &sphere);
-
-        // 🪐 Anillo gaseoso
-        let ring_rot = Vector3::new(0.4 + t * 0.2, t * 1.2, 0.3 + t * 0.7);
-        let ring_uniforms = Uniforms {
-            model_matrix: create_model_matrix(gas_screen, 80.0 * camera.zoom, ring_rot),
-            shader_type: 6,
-            base_color1: ring_color_inner,
-            base_color2: ring_color_outer,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &ring_uniforms, &ring);
-
-        // 🤖 Cibernético
-        let cyber_screen = Vector3::new(
-            (cyber_world.x - camera.pos.x) * camera.zoom,
-            (cyber_world.y - camera.pos.y) * camera.zoom,
-            cyber_world.z - camera.pos.z,
-        );
-        let cyber_rot = Vector3::new(t * 2.0, t * 1.2, t * 0.5);
-        let cyber_uniforms = Uniforms {
-            model_matrix: create_model_matrix(cyber_screen, 75.0 * camera.zoom, cyber_rot),
-            shader_type: 3,
-            base_color1: cyber_color1,
-            base_color2: cyber_color2,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &cyber_uniforms, &sphere);
-
-        // 🌋 Lava
-        let lava_screen = Vector3::new(
-            (lava_world.x - camera.pos.x) * camera.zoom,
-            (lava_world.y - camera.pos.y) * camera.zoom,
-            lava_world.z - camera.pos.z,
-        );
-        let lava_rot = Vector3::new(t * 3.0, t * 0.7, 0.0);
-        let lava_uniforms = Uniforms {
-            model_matrix: create_model_matrix(lava_screen, 65.0 * camera.zoom, lava_rot),
-            shader_type: 4,
-            base_color1: lava_color1,
-            base_color2: lava_color2,
-            light_intensity,
-            ambient_strength,
-            emission_strength,
-        };
-        render(&mut framebuffer, &lava_uniforms, &sphere);
+        space.render(&mut framebuffer, &camera, &transforms, t as f32, &mut rng);
 
-        // Captura
+        // Captura (sin pisar las anteriores)
         if window.is_key_pressed(KeyboardKey::KEY_P) {
-            framebuffer.save_image("space_render.png");
+            let path = next_free_path("space_render", ".png");
+            match framebuffer.save_image(&path) {
+                Ok(()) => println!("Captura guardada en {}", path),
+                Err(e) => eprintln!("No se pudo guardar {}: {}", path, e),
+            }
+        }
+
+        // Si falla la escritura (disco lleno...) se corta la grabación
+        if let Some(Err(e)) = recorder.as_mut().map(|active| active.record(&framebuffer)) {
+            let active = recorder.take().unwrap();
+            eprintln!("Error grabando en {}: {}", active.destination(), e);
+            stop_recording(active);
         }
 
         // Presentar
-        let texture = window
-            .load_texture_from_image(&thread, &framebuffer.image)
-            .expect("No se pudo crear textura desde framebuffer");
+        texture
+            .update_texture(&framebuffer.to_rgba_bytes())
+            .expect("No se pudo actualizar la textura del framebuffer");
 
         {
             let mut d = window.begin_drawing(&thread);
@@ -510,8 +639,8 @@ render(&mut framebuffer, &moon_uniforms, &sphere);
 
             d.draw_text(
                 &format!(
-                    "Cam X: {:.1} | Y: {:.1} | Zoom: {:.2}",
-                    camera.pos.x, camera.pos.y, camera.zoom
+                    "Cam X: {:.1} | Z: {:.1} | Zoom: {:.2}",
+                    camera.target.x, camera.target.z, camera.zoom
                 ),
                 10,
                 10,
@@ -519,17 +648,94 @@ render(&mut framebuffer, &moon_uniforms, &sphere);
                 Color::RAYWHITE,
             );
 
-            d.draw_text("1: Ir al Sol",              10, 40, 18, Color::YELLOW);
-            d.draw_text("2: Ir Planeta Rocoso",     10, 60, 18, Color::SKYBLUE);
-            d.draw_text("3: Ir Planeta Gaseoso",    10, 80, 18, Color::ORANGE);
-            d.draw_text("4: Ir Planeta Cibernético",10,100,18, Color::BLUE);
-            d.draw_text("5: Ir Planeta de Lava",    10,120,18, Color::RED);
-            d.draw_text("W/A/S/D: mover camara",10,150,16, Color::RAYWHITE);
-            d.draw_text("UP/DOWN: zoom",            10,170,16, Color::RAYWHITE);
+            for (n, &body) in warp_bodies.iter().take(warp_keys.len()).enumerate() {
+                let desc = &space.system.bodies[body];
+                let label = desc.label.as_deref().unwrap_or(&desc.name);
+                let c = desc.label_color;
+                let color = Color::new(
+                    (c.x.clamp(0.0, 1.0) * 255.0) as u8,
+                    (c.y.clamp(0.0, 1.0) * 255.0) as u8,
+                    (c.z.clamp(0.0, 1.0) * 255.0) as u8,
+                    255,
+                );
+                d.draw_text(&format!("{}: {}", n + 1, label), 10, 40 + 20 * n as i32, 18, color);
+            }
+            let controls_y = 50 + 20 * warp_bodies.len().min(warp_keys.len()) as i32;
+            d.draw_text("W/A/S/D: mover camara", 10, controls_y, 16, Color::RAYWHITE);
+            d.draw_text("UP/DOWN: zoom", 10, controls_y + 20, 16, Color::RAYWHITE);
+            d.draw_text(
+                "ESPACIO: pausa | +/-: velocidad | R: reversa | ,/.: paso",
+                10,
+                controls_y + 40,
+                16,
+                Color::RAYWHITE,
+            );
+            d.draw_text(
+                "P: captura | V: grabar video | I: grabar imagenes | L: shaders",
+                10,
+                controls_y + 60,
+                16,
+                Color::RAYWHITE,
+            );
+            d.draw_text(
+                &format!(
+                    "T: tone mapping ({}) | [/]: exposicion ({:.2}) | B: bloom ({})",
+                    framebuffer.tone_mapping.name(),
+                    framebuffer.exposure,
+                    if framebuffer.bloom.enabled { "si" } else { "no" }
+                ),
+                10,
+                controls_y + 80,
+                16,
+                Color::RAYWHITE,
+            );
+
+            // Shaders disponibles y cuántos cuerpos usan cada uno
+            if show_shaders {
+                for (n, (name, description)) in space.shaders.list().enumerate() {
+                    let used = space
+                        .system
+                        .bodies
+                        .iter()
+                        .filter(|b| b.shader_name == name)
+                        .count();
+                    d.draw_text(
+                        &format!("{} ({}): {}", name, used, description),
+                        screen_width - 330,
+                        40 + 20 * n as i32,
+                        16,
+                        Color::RAYWHITE,
+                    );
+                }
+            }
+
+            d.draw_text(
+                &format!(
+                    "Tiempo: {:.2} | x{}{}",
+                    clock.time,
+                    clock.scale,
+                    if clock.paused { " | PAUSA" } else { "" }
+                ),
+                10,
+                screen_height - 30,
+                20,
+                Color::RAYWHITE,
+            );
+
+            if let Some(active) = &recorder {
+                d.draw_text(
+                    &format!("REC {} frames", active.frames()),
+                    screen_width - 200,
+                    10,
+                    20,
+                    Color::RED,
+                );
+            }
         }
+    }
 
-        drop(texture);
-        thread::sleep(Duration::from_millis(16));
+    // Cerrar la ventana no debe dejar un video a medias
+    if let Some(active) = recorder {
+        stop_recording(active);
     }
 }
-
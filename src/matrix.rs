@@ -1,4 +1,4 @@
-use raylib::prelude::Matrix;
+use raylib::prelude::{Matrix, Vector3, Vector4};
 
 pub fn new_matrix4(
     r0c0: f32, r0c1: f32, r0c2: f32, r0c3: f32,
@@ -13,3 +13,76 @@ pub fn new_matrix4(
         m12: r0c3, m13: r1c3, m14: r2c3, m15: r3c3,
     }
 }
+
+pub fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
+    Vector4::new(
+        matrix.m0 * vector.x + matrix.m4 * vector.y + matrix.m8 * vector.z + matrix.m12 * vector.w,
+        matrix.m1 * vector.x + matrix.m5 * vector.y + matrix.m9 * vector.z + matrix.m13 * vector.w,
+        matrix.m2 * vector.x + matrix.m6 * vector.y + matrix.m10 * vector.z + matrix.m14 * vector.w,
+        matrix.m3 * vector.x + matrix.m7 * vector.y + matrix.m11 * vector.z + matrix.m15 * vector.w,
+    )
+}
+
+// División de perspectiva + viewport: clip → pantalla (x, y en píxeles, z = profundidad)
+pub fn clip_to_screen(clip: &Vector4, viewport: &Matrix) -> Vector3 {
+    let ndc = if clip.w != 0.0 {
+        Vector4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0)
+    } else {
+        Vector4::new(clip.x, clip.y, clip.z, 1.0)
+    };
+
+    let screen = multiply_matrix_vector4(viewport, &ndc);
+    Vector3::new(screen.x, screen.y, screen.z)
+}
+
+// Matriz para normales: inversa transpuesta del modelo (corrige escalas no uniformes)
+pub fn create_normal_matrix(model: &Matrix) -> Matrix {
+    let mut normal = model.inverted().transposed();
+    normal.m3 = 0.0;
+    normal.m7 = 0.0;
+    normal.m11 = 0.0;
+    normal.m12 = 0.0;
+    normal.m13 = 0.0;
+    normal.m14 = 0.0;
+    normal.m15 = 1.0;
+    normal
+}
+
+// Matriz de vista: lleva el mundo al espacio de la cámara (cámara mirando hacia -Z)
+pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix {
+    let forward = (target - eye).normalized();
+    let right = forward.cross(up).normalized();
+    let up = right.cross(forward);
+
+    new_matrix4(
+        right.x,    right.y,    right.z,    -right.dot(eye),
+        up.x,       up.y,       up.z,       -up.dot(eye),
+        -forward.x, -forward.y, -forward.z, forward.dot(eye),
+        0.0,        0.0,        0.0,        1.0,
+    )
+}
+
+// Proyección en perspectiva (estilo OpenGL, z de clip en [-w, w])
+pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix {
+    let f = 1.0 / (fov_y * 0.5).tan();
+
+    new_matrix4(
+        f / aspect, 0.0, 0.0,                          0.0,
+        0.0,        f,   0.0,                          0.0,
+        0.0,        0.0, (far + near) / (near - far),  2.0 * far * near / (near - far),
+        0.0,        0.0, -1.0,                         0.0,
+    )
+}
+
+// Viewport: NDC [-1, 1] → píxeles (y hacia abajo) y profundidad en [0, 1]
+pub fn create_viewport_matrix(x: f32, y: f32, width: f32, height: f32) -> Matrix {
+    let half_w = width * 0.5;
+    let half_h = height * 0.5;
+
+    new_matrix4(
+        half_w, 0.0,     0.0, x + half_w,
+        0.0,    -half_h, 0.0, y + half_h,
+        0.0,    0.0,     0.5, 0.5,
+        0.0,    0.0,     0.0, 1.0,
+    )
+}
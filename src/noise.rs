@@ -0,0 +1,273 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use raylib::prelude::*;
+
+// Ruido procedural 3D para los shaders de planetas. Todo sale de una tabla de
+// permutación barajada con la semilla: misma semilla, mismo planeta.
+
+// Direcciones de gradiente del ruido simplex (aristas de un cubo)
+const GRAD3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// Producto del gradiente elegido por `hash` con (x, y, z) (Perlin mejorado)
+fn grad(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[derive(Clone)]
+pub struct Noise {
+    perm: [u8; 512], // permutación de 0..256 repetida dos veces (evita el módulo)
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let mut perm = [0u8; 512];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = table[i & 255];
+        }
+        Noise { perm }
+    }
+
+    fn hash(&self, i: i32, j: i32, k: i32) -> u8 {
+        let a = self.perm[(i & 255) as usize] as usize;
+        let b = self.perm[a + (j & 255) as usize] as usize;
+        self.perm[b + (k & 255) as usize]
+    }
+
+    /// Ruido de gradiente (Perlin) en [-1, 1] aprox.
+    pub fn perlin(&self, p: Vector3) -> f32 {
+        let (xf, yf, zf) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (i, j, k) = (xf as i32, yf as i32, zf as i32);
+        let (x, y, z) = (p.x - xf, p.y - yf, p.z - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let corner = |di: i32, dj: i32, dk: i32| {
+            let h = self.hash(i + di, j + dj, k + dk);
+            grad(h, x - di as f32, y - dj as f32, z - dk as f32)
+        };
+
+        lerp(
+            lerp(
+                lerp(corner(0, 0, 0), corner(1, 0, 0), u),
+                lerp(corner(0, 1, 0), corner(1, 1, 0), u),
+                v,
+            ),
+            lerp(
+                lerp(corner(0, 0, 1), corner(1, 0, 1), u),
+                lerp(corner(0, 1, 1), corner(1, 1, 1), u),
+                v,
+            ),
+            w,
+        )
+    }
+
+    /// Ruido simplex 3D en [-1, 1] aprox.: menos artefactos de rejilla que Perlin
+    pub fn simplex(&self, p: Vector3) -> f32 {
+        const F3: f32 = 1.0 / 3.0;
+        const G3: f32 = 1.0 / 6.0;
+
+        // Celda simplex que contiene al punto (espacio sesgado)
+        let s = (p.x + p.y + p.z) * F3;
+        let (i, j, k) = ((p.x + s).floor(), (p.y + s).floor(), (p.z + s).floor());
+        let t = (i + j + k) * G3;
+        let x0 = p.x - (i - t);
+        let y0 = p.y - (j - t);
+        let z0 = p.z - (k - t);
+
+        // Qué tetraedro de la celda: orden de las coordenadas
+        let (i1, j1, k1, i2, j2, k2) = if x0 >= y0 {
+            if y0 >= z0 {
+                (1, 0, 0, 1, 1, 0)
+            } else if x0 >= z0 {
+                (1, 0, 0, 1, 0, 1)
+            } else {
+                (0, 0, 1, 1, 0, 1)
+            }
+        } else if y0 < z0 {
+            (0, 0, 1, 0, 1, 1)
+        } else if x0 < z0 {
+            (0, 1, 0, 0, 1, 1)
+        } else {
+            (0, 1, 0, 1, 1, 0)
+        };
+
+        let offsets = [
+            (0, 0, 0, x0, y0, z0),
+            (i1, j1, k1, x0 - i1 as f32 + G3, y0 - j1 as f32 + G3, z0 - k1 as f32 + G3),
+            (i2, j2, k2, x0 - i2 as f32 + 2.0 * G3, y0 - j2 as f32 + 2.0 * G3, z0 - k2 as f32 + 2.0 * G3),
+            (1, 1, 1, x0 - 1.0 + 3.0 * G3, y0 - 1.0 + 3.0 * G3, z0 - 1.0 + 3.0 * G3),
+        ];
+
+        let (i, j, k) = (i as i32, j as i32, k as i32);
+        let total: f32 = offsets
+            .iter()
+            .map(|&(di, dj, dk, x, y, z)| {
+                let t = 0.6 - x * x - y * y - z * z;
+                if t < 0.0 {
+                    return 0.0;
+                }
+                let g = GRAD3[self.hash(i + di, j + dj, k + dk) as usize % 12];
+                let t2 = t * t;
+                t2 * t2 * (g[0] * x + g[1] * y + g[2] * z)
+            })
+            .sum();
+
+        32.0 * total
+    }
+
+    /// Movimiento browniano fraccional: suma de octavas de Perlin, en [-1, 1] aprox.
+    /// `lacunarity` multiplica la frecuencia y `gain` la amplitud en cada octava.
+    pub fn fbm(&self, p: Vector3, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut norm = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        for _ in 0..octaves {
+            sum += amplitude * self.perlin(p * frequency);
+            norm += amplitude;
+            amplitude *= gain;
+            frequency *= lacunarity;
+        }
+        if norm > 0.0 { sum / norm } else { 0.0 }
+    }
+
+    /// Multifractal de crestas en [0, 1]: cordilleras afiladas. Cada octava pesa
+    /// según la anterior, así el detalle se concentra sobre las crestas.
+    pub fn ridged(&self, p: Vector3, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut norm = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut weight = 1.0;
+        for _ in 0..octaves {
+            let ridge = 1.0 - self.perlin(p * frequency).abs();
+            let signal = ridge * ridge * weight;
+            weight = (signal * 2.0).clamp(0.0, 1.0);
+
+            sum += amplitude * signal;
+            norm += amplitude;
+            amplitude *= gain;
+            frequency *= lacunarity;
+        }
+        if norm > 0.0 { sum / norm } else { 0.0 }
+    }
+
+    /// Ruido celular (Worley): distancias al punto característico más cercano
+    /// y al segundo más cercano. `f2 - f1` vale 0 en los bordes entre celdas.
+    pub fn worley(&self, p: Vector3) -> (f32, f32) {
+        let (i, j, k) = (p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32);
+        let mut f1 = f32::MAX;
+        let mut f2 = f32::MAX;
+
+        for dk in -1..=1 {
+            for dj in -1..=1 {
+                for di in -1..=1 {
+                    let (ci, cj, ck) = (i + di, j + dj, k + dk);
+                    // Un punto por celda, en una posición fija según su hash
+                    let h = self.hash(ci, cj, ck) as usize;
+                    let feature = Vector3::new(
+                        ci as f32 + self.perm[h] as f32 / 255.0,
+                        cj as f32 + self.perm[h + 1] as f32 / 255.0,
+                        ck as f32 + self.perm[h + 2] as f32 / 255.0,
+                    );
+
+                    let d = (feature - p).length();
+                    if d < f1 {
+                        f2 = f1;
+                        f1 = d;
+                    } else if d < f2 {
+                        f2 = d;
+                    }
+                }
+            }
+        }
+
+        (f1, f2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Puntos repartidos por el espacio, incluidos negativos y lejos del origen
+    fn points() -> impl Iterator<Item = Vector3> {
+        (0..2000).map(|i| {
+            let f = i as f32;
+            Vector3::new(
+                (f * 0.731).sin() * 40.0 + f * 0.013,
+                (f * 1.379).cos() * 25.0 - f * 0.007,
+                (f * 0.291).sin() * 60.0,
+            )
+        })
+    }
+
+    #[test]
+    fn perlin_and_simplex_stay_in_range() {
+        let noise = Noise::new(11);
+        for p in points() {
+            let (perlin, simplex) = (noise.perlin(p), noise.simplex(p));
+            assert!((-1.0..=1.0).contains(&perlin), "perlin({:?}) = {}", p, perlin);
+            assert!((-1.0..=1.0).contains(&simplex), "simplex({:?}) = {}", p, simplex);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_noise() {
+        let (a, b) = (Noise::new(42), Noise::new(42));
+        for p in points() {
+            assert_eq!(a.perlin(p), b.perlin(p));
+            assert_eq!(a.simplex(p), b.simplex(p));
+            assert_eq!(a.worley(p), b.worley(p));
+        }
+    }
+
+    #[test]
+    fn different_seeds_differ() {
+        let (a, b) = (Noise::new(1), Noise::new(2));
+        let differing = points().filter(|&p| (a.perlin(p) - b.perlin(p)).abs() > 1e-3).count();
+        assert!(differing > 1000, "solo {} puntos distintos", differing);
+    }
+
+    #[test]
+    fn worley_distances_are_ordered() {
+        let noise = Noise::new(5);
+        for p in points() {
+            let (f1, f2) = noise.worley(p);
+            assert!(0.0 <= f1 && f1 <= f2, "worley({:?}) = ({}, {})", p, f1, f2);
+        }
+    }
+}
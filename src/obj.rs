@@ -16,7 +16,7 @@ impl Obj {
         for model in models {
             let mesh = &model.mesh;
             for i in 0..(mesh.positions.len() / 3) {
-                let position = Vector3::new(mesh.positions[i * 3], -mesh.positions[i * 3 + 1], mesh.positions[i * 3 + 2]);
+                let position = Vector3::new(mesh.positions[i * 3], mesh.positions[i * 3 + 1], mesh.positions[i * 3 + 2]);
                 let normal = if !mesh.normals.is_empty() {
                     Vector3::new(mesh.normals[i * 3], mesh.normals[i * 3 + 1], mesh.normals[i * 3 + 2])
                 } else {
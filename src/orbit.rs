@@ -0,0 +1,173 @@
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+// Elementos orbitales keplerianos. Los ángulos van en radianes.
+// El plano de referencia es la eclíptica (XZ) y el eje Y apunta al "norte".
+#[derive(Clone, Copy, Debug)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f32,    // a
+    pub eccentricity: f32,       // e, en [0, 1)
+    pub inclination: f32,        // i
+    pub ascending_node: f32,     // Ω, longitud del nodo ascendente
+    pub arg_periapsis: f32,      // ω, argumento del periapsis
+    pub mean_anomaly_epoch: f32, // M0, anomalía media en t = 0
+    pub mean_motion: f32,        // n, radianes por unidad de tiempo de simulación
+}
+
+/// Ángulo calculado en f64 reducido a [0, 2π) y pasado a f32
+pub fn wrap_angle(angle: f64) -> f32 {
+    angle.rem_euclid(std::f64::consts::TAU) as f32
+}
+
+/// Resuelve la ecuación de Kepler `M = E - e·sin(E)` por Newton-Raphson
+/// y devuelve la anomalía excéntrica `E`.
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    // M en [-π, π] para que el punto de partida esté cerca de la solución
+    let m = (mean_anomaly + PI).rem_euclid(2.0 * PI) - PI;
+    let e = eccentricity;
+
+    // Con excentricidades altas, E = π converge mejor que E = M
+    let mut ecc_anomaly = if e > 0.8 { PI.copysign(m) } else { m };
+
+    for _ in 0..16 {
+        let f = ecc_anomaly - e * ecc_anomaly.sin() - m;
+        let df = 1.0 - e * ecc_anomaly.cos();
+        let step = f / df;
+        ecc_anomaly -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+
+    ecc_anomaly
+}
+
+impl OrbitalElements {
+    // Órbita circular en la eclíptica (lo que había antes de los elementos completos)
+    pub fn circular(radius: f32, speed: f32, phase: f32) -> Self {
+        OrbitalElements {
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            ascending_node: 0.0,
+            arg_periapsis: 0.0,
+            mean_anomaly_epoch: phase,
+            mean_motion: speed,
+        }
+    }
+
+    /// Posición relativa al cuerpo central en el tiempo de simulación `t`
+    pub fn position(&self, t: f64) -> Vector3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+
+        // En f64 y reducida a una vuelta antes de pasar a f32: con t grande el
+        // producto en f32 perdería los decimales y la órbita saltaría
+        let mean_anomaly = wrap_angle(self.mean_anomaly_epoch as f64 + self.mean_motion as f64 * t);
+        let ecc_anomaly = solve_kepler(mean_anomaly, e);
+
+        // Anomalía verdadera y distancia al foco
+        let true_anomaly = 2.0
+            * ((1.0 + e).sqrt() * (ecc_anomaly * 0.5).sin())
+                .atan2((1.0 - e).sqrt() * (ecc_anomaly * 0.5).cos());
+        let r = a * (1.0 - e * ecc_anomaly.cos());
+
+        // Del plano orbital al de referencia: rotar ω, inclinar i, rotar Ω
+        let u = self.arg_periapsis + true_anomaly; // argumento de latitud
+        let (sin_u, cos_u) = u.sin_cos();
+        let (sin_o, cos_o) = self.ascending_node.sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+
+        let x = r * (cos_o * cos_u - sin_o * sin_u * cos_i);
+        let y = r * (sin_o * cos_u + cos_o * sin_u * cos_i);
+        let z = r * (sin_u * sin_i);
+
+        // (x, y) quedan en la eclíptica XZ; z es la altura sobre ella.
+        // Ojo: cambiar y por z invierte la orientación. Con n > 0 el cuerpo va de +X
+        // hacia +Z, que mirado desde +Y (el norte) es en sentido horario: ese es el
+        // sentido "directo" de este sistema. La inclinación sí sube hacia +Y.
+        Vector3::new(x, z, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Residuo de la ecuación de Kepler llevado a [-π, π]
+    fn kepler_residual(ecc_anomaly: f32, eccentricity: f32, mean_anomaly: f32) -> f32 {
+        let r = ecc_anomaly - eccentricity * ecc_anomaly.sin() - mean_anomaly;
+        (r + PI).rem_euclid(2.0 * PI) - PI
+    }
+
+    fn mean_anomalies() -> impl Iterator<Item = f32> {
+        (0..=200).map(|i| -2.0 * PI + 4.0 * PI * i as f32 / 200.0)
+    }
+
+    #[test]
+    fn circular_orbit_has_e_equal_m() {
+        for i in 0..=100 {
+            let m = -PI + 2.0 * PI * i as f32 / 100.0 * 0.999;
+            assert!((solve_kepler(m, 0.0) - m).abs() < 1e-6, "M = {}", m);
+        }
+    }
+
+    #[test]
+    fn kepler_residual_is_small() {
+        for e in [0.1, 0.5, 0.95] {
+            for m in mean_anomalies() {
+                let ecc_anomaly = solve_kepler(m, e);
+                let residual = kepler_residual(ecc_anomaly, e, m);
+                assert!(residual.abs() < 1e-5, "e = {}, M = {}: residuo {}", e, m, residual);
+            }
+        }
+    }
+
+    fn elements(eccentricity: f32, inclination: f32) -> OrbitalElements {
+        OrbitalElements {
+            semi_major_axis: 10.0,
+            eccentricity,
+            inclination,
+            ascending_node: 0.7,
+            arg_periapsis: 1.3,
+            mean_anomaly_epoch: 0.0,
+            mean_motion: 1.0,
+        }
+    }
+
+    #[test]
+    fn periapsis_and_apoapsis_radii() {
+        for e in [0.0, 0.3, 0.9] {
+            let orbit = elements(e, 0.4);
+            let periapsis = orbit.position(0.0).length();
+            let apoapsis = orbit.position(std::f64::consts::PI).length();
+            assert!((periapsis - 10.0 * (1.0 - e)).abs() < 1e-4, "e = {}: {}", e, periapsis);
+            assert!((apoapsis - 10.0 * (1.0 + e)).abs() < 1e-4, "e = {}: {}", e, apoapsis);
+        }
+    }
+
+    #[test]
+    fn zero_inclination_stays_on_the_ecliptic() {
+        let orbit = elements(0.5, 0.0);
+        for i in 0..100 {
+            let p = orbit.position(i as f64 * 0.17);
+            assert!(p.y.abs() < 1e-5, "t = {}: y = {}", i, p.y);
+        }
+    }
+
+    #[test]
+    fn prograde_goes_from_x_towards_z() {
+        // Cambio de ejes documentado en `position`: n > 0 avanza de +X hacia +Z
+        let orbit = OrbitalElements::circular(1.0, 1.0, 0.0);
+        let start = orbit.position(0.0);
+        let later = orbit.position(0.1);
+        assert!((start.x - 1.0).abs() < 1e-6 && start.z.abs() < 1e-6);
+        assert!(later.z > 0.0);
+        // Visto desde +Y: (start × later) apunta a -Y, es decir, sentido horario
+        assert!(start.cross(later).y < 0.0);
+
+        // Con inclinación, el argumento de latitud π/2 queda por encima de la eclíptica
+        let tilted = OrbitalElements { inclination: 0.5, ..OrbitalElements::circular(1.0, 1.0, 0.0) };
+        assert!(tilted.position(std::f64::consts::FRAC_PI_2).y > 0.0);
+    }
+}
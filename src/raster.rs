@@ -0,0 +1,179 @@
+use crate::clipping;
+use crate::framebuffer::Framebuffer;
+use crate::obj::Obj;
+use crate::shaders::fragment_shader;
+use crate::triangle;
+use crate::vertex::Vertex;
+use crate::Uniforms;
+use raylib::math::Vector3;
+use std::thread;
+
+// Lado de cada tile en píxeles
+pub const TILE_SIZE: usize = 64;
+
+// Triángulo ya recortado y en coordenadas de pantalla, listo para rasterizar
+pub struct ScreenTriangle {
+    pub vertices: [Vertex; 3],
+    pub uniforms: usize, // índice en la lista de uniforms del frame
+}
+
+/// Etapa de geometría: vertex shader + recorte. Agrega los triángulos del
+/// objeto a `triangles`, que luego se rasterizan todos juntos por tiles.
+pub fn assemble(
+    triangles: &mut Vec<ScreenTriangle>,
+    uniforms_index: usize,
+    uniforms: &Uniforms,
+    obj: &Obj,
+) {
+    let transformed: Vec<Vertex> = obj
+        .vertices
+        .iter()
+        .map(|v| uniforms.shader.vertex(v, uniforms))
+        .collect();
+
+    for face in obj.indices.chunks(3) {
+        let v0 = &transformed[face[0] as usize];
+        let v1 = &transformed[face[1] as usize];
+        let v2 = &transformed[face[2] as usize];
+
+        // Recorte contra el frustum antes de rasterizar
+        for vertices in clipping::clip_triangle(v0, v1, v2, &uniforms.viewport_matrix) {
+            triangles.push(ScreenTriangle {
+                vertices,
+                uniforms: uniforms_index,
+            });
+        }
+    }
+}
+
+// Franja horizontal de tiles: sus filas son contiguas en memoria, así que cada
+// hilo puede escribir en la suya sin sincronización
+struct Band<'a> {
+    row: usize,
+    color: &'a mut [Vector3],
+    depth: &'a mut [f32],
+}
+
+// Profundidad media en pantalla (mayor = más lejos)
+fn mean_depth(tri: &ScreenTriangle) -> f32 {
+    let [v0, v1, v2] = &tri.vertices;
+    (v0.transformed_position.z + v1.transformed_position.z + v2.transformed_position.z) / 3.0
+}
+
+/// Rasteriza y sombrea los triángulos en paralelo. La pantalla se divide en
+/// tiles, cada triángulo se asigna a los tiles que toca su caja envolvente y
+/// cada franja de tiles se procesa en un hilo, escribiendo directo en los
+/// buffers del framebuffer.
+///
+/// Hay dos pasadas: primero los opacos en orden de envío y después los
+/// transparentes ordenados de atrás hacia adelante, para que cada uno se
+/// mezcle sobre todo lo que tiene detrás.
+pub fn rasterize(framebuffer: &mut Framebuffer, triangles: &[ScreenTriangle], uniforms: &[Uniforms]) {
+    let width = framebuffer.width as usize;
+    let height = framebuffer.height as usize;
+    let tiles_x = width.div_ceil(TILE_SIZE);
+    let tiles_y = height.div_ceil(TILE_SIZE);
+
+    let (opaque, mut transparent): (Vec<usize>, Vec<usize>) =
+        (0..triangles.len()).partition(|&i| uniforms[triangles[i].uniforms].blend.mode.is_opaque());
+    transparent.sort_by(|&a, &b| mean_depth(&triangles[b]).total_cmp(&mean_depth(&triangles[a])));
+
+    // Binning: lista de triángulos por tile (el orden de las pasadas se conserva en cada tile)
+    let mut bins: Vec<Vec<u32>> = vec![Vec::new(); tiles_x * tiles_y];
+    for i in opaque.into_iter().chain(transparent) {
+        let tri = &triangles[i];
+        let [p0, p1, p2] = tri.vertices.each_ref().map(|v| v.transformed_position);
+
+        let min_x = p0.x.min(p1.x).min(p2.x).floor().max(0.0) as usize;
+        let min_y = p0.y.min(p1.y).min(p2.y).floor().max(0.0) as usize;
+        let max_x = p0.x.max(p1.x).max(p2.x).ceil().max(0.0) as usize;
+        let max_y = p0.y.max(p1.y).max(p2.y).ceil().max(0.0) as usize;
+        if min_x >= width || min_y >= height {
+            continue;
+        }
+
+        let max_tx = (max_x / TILE_SIZE).min(tiles_x - 1);
+        let max_ty = (max_y / TILE_SIZE).min(tiles_y - 1);
+        for ty in min_y / TILE_SIZE..=max_ty {
+            for tx in min_x / TILE_SIZE..=max_tx {
+                bins[ty * tiles_x + tx].push(i as u32);
+            }
+        }
+    }
+
+    let band_len = TILE_SIZE * width;
+    let bands = framebuffer
+        .color_buffer
+        .chunks_mut(band_len)
+        .zip(framebuffer.depth_buffer.chunks_mut(band_len))
+        .enumerate()
+        .map(|(row, (color, depth))| Band { row, color, depth });
+
+    // Reparto de franjas entre hilos (intercaladas para equilibrar la carga)
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(tiles_y);
+    let mut work: Vec<Vec<Band>> = (0..workers).map(|_| Vec::new()).collect();
+    for (i, band) in bands.enumerate() {
+        work[i % workers].push(band);
+    }
+
+    let bins = &bins;
+    thread::scope(|scope| {
+        for bands in work {
+            scope.spawn(move || {
+                for mut band in bands {
+                    for tx in 0..tiles_x {
+                        let bin = &bins[band.row * tiles_x + tx];
+                        rasterize_tile(&mut band, tx, width, bin, triangles, uniforms);
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn rasterize_tile(
+    band: &mut Band,
+    tile_x: usize,
+    width: usize,
+    bin: &[u32],
+    triangles: &[ScreenTriangle],
+    uniforms: &[Uniforms],
+) {
+    let band_y = band.row * TILE_SIZE;
+    let rows = band.depth.len() / width;
+    let min = ((tile_x * TILE_SIZE) as i32, band_y as i32);
+    let max = (
+        ((tile_x + 1) * TILE_SIZE).min(width) as i32,
+        (band_y + rows) as i32,
+    );
+
+    for &i in bin {
+        let tri = &triangles[i as usize];
+        let uniforms = &uniforms[tri.uniforms];
+        let [v0, v1, v2] = &tri.vertices;
+
+        triangle::triangle(v0, v1, v2, min, max, |frag| {
+            let x = frag.position.x as usize;
+            let y = frag.position.y as usize - band_y;
+            let idx = y * width + x;
+
+            // ⭐ Test de profundidad antes de sombrear: no gastamos shader en píxeles ocultos
+            if !frag.depth.is_finite() || frag.depth >= band.depth[idx] {
+                return;
+            }
+
+            let (color, alpha) = fragment_shader(&frag, uniforms);
+            let blend = uniforms.blend;
+            if blend.mode.is_opaque() {
+                band.color[idx] = color;
+            } else if alpha > 0.0 {
+                band.color[idx] = blend.mode.blend(band.color[idx], color, alpha);
+            } else {
+                return; // totalmente transparente: ni color ni profundidad
+            }
+            if blend.depth_write {
+                band.depth[idx] = frag.depth;
+            }
+        });
+    }
+}
@@ -0,0 +1,79 @@
+use crate::framebuffer::Framebuffer;
+use crate::image_io::Y4mWriter;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Grabación de frames: secuencia de imágenes numeradas o un video Y4M.
+// Quien graba decide el paso de simulación de cada frame, así el resultado
+// sale fluido aunque el render vaya más lento que el tiempo real.
+
+// Cuadros por segundo de las grabaciones interactivas
+pub const RECORD_FPS: u32 = 60;
+
+enum Output {
+    Images { dir: PathBuf, extension: &'static str },
+    Video(Y4mWriter),
+}
+
+pub struct Recorder {
+    output: Output,
+    destination: String,
+    frames: u32,
+}
+
+/// Primer nombre `{prefix}_NNNN{suffix}` que todavía no existe
+pub fn next_free_path(prefix: &str, suffix: &str) -> String {
+    (1..)
+        .map(|n| format!("{}_{:04}{}", prefix, n, suffix))
+        .find(|path| !Path::new(path).exists())
+        .unwrap()
+}
+
+impl Recorder {
+    /// Un archivo por frame (`frame_0000.png`, ...) dentro de `dir`
+    pub fn images(dir: &str, extension: &'static str) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Recorder {
+            output: Output::Images { dir: PathBuf::from(dir), extension },
+            destination: dir.to_string(),
+            frames: 0,
+        })
+    }
+
+    /// Todos los frames en un único video Y4M
+    pub fn video(path: &str, width: u32, height: u32, fps: u32) -> io::Result<Self> {
+        Ok(Recorder {
+            output: Output::Video(Y4mWriter::create(path, width, height, fps)?),
+            destination: path.to_string(),
+            frames: 0,
+        })
+    }
+
+    pub fn record(&mut self, framebuffer: &Framebuffer) -> io::Result<()> {
+        match &mut self.output {
+            Output::Images { dir, extension } => {
+                let path = dir.join(format!("frame_{:04}.{}", self.frames, extension));
+                framebuffer.save_image(&path.to_string_lossy())?;
+            }
+            Output::Video(video) => video.write_frame(&framebuffer.to_rgba_bytes())?,
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    // Cierra la grabación (el video necesita vaciar el buffer de escritura)
+    pub fn finish(self) -> io::Result<()> {
+        match self.output {
+            Output::Images { .. } => Ok(()),
+            Output::Video(video) => video.finish(),
+        }
+    }
+}
@@ -0,0 +1,625 @@
+use crate::bloom::Bloom;
+use crate::framebuffer::{BlendMode, BlendState};
+use crate::orbit::{wrap_angle, OrbitalElements};
+use crate::tonemap::{srgb_color, ToneMapping};
+use crate::shaders::{Material, PlanetShader, ShaderParams, ShaderRegistry};
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// Descripción del sistema estelar cargada desde un archivo (subconjunto de TOML):
+//   clave = valor            valores: "texto", número, true/false, [x, y, z]
+//   [[body]]                 empieza un cuerpo nuevo
+//   params.clave = valor     parámetro propio del shader del cuerpo
+//   # comentario
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Str(String),
+    Num(f32),
+    Bool(bool),
+    Array(Vec<f32>),
+}
+
+type Table = HashMap<String, Value>;
+
+const SYSTEM_KEYS: [&str; 9] = [
+    "light_intensity",
+    "ambient_strength",
+    "emission_strength",
+    "exposure",
+    "tone_mapping",
+    "bloom",
+    "bloom_threshold",
+    "bloom_intensity",
+    "bloom_levels",
+];
+
+// Semilla de ruido más grande que acepta un cuerpo
+const MAX_SEED: u32 = 1_000_000;
+
+const BODY_KEYS: [&str; 29] = [
+    "name",
+    "label",
+    "label_color",
+    "parent",
+    "mesh",
+    "shader",
+    "scale",
+    "orbit_radius",
+    "orbit_speed",
+    "orbit_phase",
+    "eccentricity",
+    "inclination",
+    "ascending_node",
+    "arg_periapsis",
+    "rotation",
+    "rotation_speed",
+    "color1",
+    "color2",
+    "seed",
+    "blend",
+    "depth_write",
+    "specular",
+    "shininess",
+    "metallic",
+    "atmosphere_color",
+    "atmosphere_sunset",
+    "atmosphere_thickness",
+    "atmosphere_density",
+    "warp",
+];
+
+// Capa de atmósfera alrededor de un cuerpo: la misma malla, un poco más
+// grande, dibujada con el shader "atmosphere"
+pub struct AtmosphereDesc {
+    pub color: Vector3,        // dispersión del lado de día
+    pub sunset_color: Vector3, // tono cerca del terminador
+    pub shader: Arc<dyn PlanetShader>,
+}
+
+pub struct BodyDesc {
+    pub name: String,
+    pub label: Option<String>, // texto del HUD para el warp
+    pub label_color: Vector3,
+    pub parent: Option<usize>, // índice en `bodies`, siempre anterior al hijo
+    pub mesh: usize,           // índice en `mesh_paths`
+    pub shader_name: String,
+    pub shader: Arc<dyn PlanetShader>,
+    pub scale: f32,
+    pub orbit: OrbitalElements,
+    pub rotation: Vector3,
+    pub rotation_speed: Vector3,
+    pub color1: Vector3,
+    pub color2: Vector3,
+    pub seed: u32, // variación del ruido de los shaders entre cuerpos con el mismo look
+    pub blend: BlendState,
+    pub material: Material,
+    pub atmosphere: Option<AtmosphereDesc>,
+    pub warp: bool,
+}
+
+pub struct SystemDesc {
+    pub bodies: Vec<BodyDesc>,
+    pub mesh_paths: Vec<String>,
+    pub light_intensity: f32,
+    pub ambient_strength: f32,
+    pub emission_strength: f32,
+    pub exposure: f32,
+    pub tone_mapping: ToneMapping,
+    pub bloom: Bloom,
+}
+
+// Prefijo de los parámetros de shader dentro de un [[body]]
+const PARAMS_PREFIX: &str = "params.";
+
+// El archivo del sistema es un subconjunto de TOML, una clave por línea:
+//   - textos entre comillas dobles, con los escapes \" \\ \n y \t
+//   - números (sin `_` ni hex), true / false y listas de números en una sola línea
+//   - tablas `[[body]]`; no hay otras tablas, ni tablas en línea, ni textos multilínea
+// Lo que sale de eso es un error explícito, no algo que se lea a medias.
+fn parse_value(raw: &str) -> Result<Value, String> {
+    let raw = raw.trim();
+
+    if let Some(text) = raw.strip_prefix('"') {
+        return parse_string(text).map(Value::Str);
+    }
+    if raw.starts_with('\'') {
+        return Err("los textos van entre comillas dobles".to_string());
+    }
+    if raw.starts_with('{') {
+        return Err("no se admiten tablas en línea `{ ... }`".to_string());
+    }
+    if let Some(items) = raw.strip_prefix('[') {
+        let items = items
+            .strip_suffix(']')
+            .ok_or("las listas deben abrirse y cerrarse en la misma línea")?;
+        return items
+            .split(',')
+            .map(|n| parse_number(n.trim()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    match raw {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => parse_number(raw).map(Value::Num),
+    }
+}
+
+fn parse_number(raw: &str) -> Result<f32, String> {
+    if raw.contains('_') {
+        return Err(format!("número `{}`: no se admiten separadores `_`", raw));
+    }
+    match raw.parse::<f32>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(format!("valor inválido `{}`", raw)),
+    }
+}
+
+// Contenido de un texto, desde después de la comilla de apertura
+fn parse_string(text: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                return if chars.as_str().trim().is_empty() {
+                    Ok(out)
+                } else {
+                    Err("texto inesperado después de las comillas".to_string())
+                };
+            }
+            '\\' => out.push(match chars.next() {
+                Some('"') => '"',
+                Some('\\') => '\\',
+                Some('n') => '\n',
+                Some('t') => '\t',
+                other => return Err(format!("escape no admitido `\\{}`", other.map_or(String::new(), String::from))),
+            }),
+            _ => out.push(c),
+        }
+    }
+    Err("texto sin cerrar (no se admiten textos multilínea)".to_string())
+}
+
+// Corta el comentario de la línea: el primer '#' que no esté dentro de un texto
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// Devuelve las claves globales y la lista de tablas [[body]] (con su línea)
+fn parse_tables(text: &str) -> Result<(Table, Vec<(usize, Table)>), String> {
+    let mut globals = Table::new();
+    let mut bodies: Vec<(usize, Table)> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line == "[[body]]" {
+            bodies.push((line_no, Table::new()));
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(format!("línea {}: tabla no admitida `{}` (solo [[body]])", line_no, line));
+        }
+
+        let (key, raw) = line
+            .split_once('=')
+            .ok_or_else(|| format!("línea {}: se esperaba `clave = valor`", line_no))?;
+        let key = key.trim().to_string();
+        let value = parse_value(raw).map_err(|e| format!("línea {}: `{}`: {}", line_no, key, e))?;
+
+        let (table, allowed, is_body): (&mut Table, &[&str], bool) = match bodies.last_mut() {
+            Some((_, table)) => (table, &BODY_KEYS, true),
+            None => (&mut globals, &SYSTEM_KEYS, false),
+        };
+        // Los parámetros de shader los valida el registro al crear el shader
+        let is_param = is_body && key.starts_with(PARAMS_PREFIX);
+        if !allowed.contains(&key.as_str()) && !is_param {
+            return Err(format!("línea {}: clave desconocida `{}`", line_no, key));
+        }
+        if table.insert(key.clone(), value).is_some() {
+            return Err(format!("línea {}: clave repetida `{}`", line_no, key));
+        }
+    }
+
+    Ok((globals, bodies))
+}
+
+fn get_f32(table: &Table, key: &str, default: f32) -> Result<f32, String> {
+    match table.get(key) {
+        None => Ok(default),
+        Some(Value::Num(n)) => Ok(*n),
+        Some(_) => Err(format!("`{}` debe ser un número", key)),
+    }
+}
+
+fn get_vec3(table: &Table, key: &str, default: Vector3) -> Result<Vector3, String> {
+    match table.get(key) {
+        None => Ok(default),
+        Some(Value::Array(v)) if v.len() == 3 => Ok(Vector3::new(v[0], v[1], v[2])),
+        Some(_) => Err(format!("`{}` debe ser una lista [x, y, z]", key)),
+    }
+}
+
+// Los colores del archivo están en sRGB (como en cualquier selector de color);
+// el render trabaja en luz lineal
+fn get_color(table: &Table, key: &str, default: Vector3) -> Result<Vector3, String> {
+    get_vec3(table, key, default).map(srgb_color)
+}
+
+fn get_str<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>, String> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(Value::Str(s)) => Ok(Some(s)),
+        Some(_) => Err(format!("`{}` debe ser un texto entre comillas", key)),
+    }
+}
+
+// Bloom del sistema: apagado salvo `bloom = true`
+fn parse_bloom(globals: &Table) -> Result<Bloom, String> {
+    let levels = get_f32(globals, "bloom_levels", Bloom::OFF.levels as f32)?;
+    if !(1.0..=8.0).contains(&levels) || levels.fract() != 0.0 {
+        return Err("`bloom_levels` debe ser un entero entre 1 y 8".to_string());
+    }
+    let threshold = get_f32(globals, "bloom_threshold", Bloom::OFF.threshold)?;
+    if threshold <= 0.0 {
+        return Err("`bloom_threshold` debe ser mayor que cero".to_string());
+    }
+    Ok(Bloom {
+        enabled: get_bool(globals, "bloom")?,
+        threshold,
+        intensity: get_f32(globals, "bloom_intensity", Bloom::OFF.intensity)?,
+        levels: levels as u32,
+    })
+}
+
+fn get_bool(table: &Table, key: &str) -> Result<bool, String> {
+    match table.get(key) {
+        None => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err(format!("`{}` debe ser true o false", key)),
+    }
+}
+
+impl SystemDesc {
+    /// Carga el sistema desde disco; las mallas se buscan junto al archivo
+    /// y los shaders se crean con `registry`
+    pub fn load(path: &str, registry: &ShaderRegistry) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("no se pudo leer {}: {}", path, e))?;
+        let base_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        Self::parse(&text, base_dir, registry).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(text: &str, base_dir: &Path, registry: &ShaderRegistry) -> Result<Self, String> {
+        let (globals, tables) = parse_tables(text)?;
+
+        let mut bodies: Vec<BodyDesc> = Vec::with_capacity(tables.len());
+        let mut mesh_paths: Vec<String> = Vec::new();
+
+        for (line_no, table) in &tables {
+            let body = Self::parse_body(table, &bodies, base_dir, registry, &mut mesh_paths)
+                .map_err(|e| format!("cuerpo en línea {}: {}", line_no, e))?;
+            bodies.push(body);
+        }
+
+        if bodies.is_empty() {
+            return Err("el sistema no tiene ningún [[body]]".to_string());
+        }
+
+        let exposure = get_f32(&globals, "exposure", 1.0)?;
+        if !exposure.is_finite() || exposure <= 0.0 {
+            return Err("`exposure` debe ser mayor que cero".to_string());
+        }
+
+        Ok(SystemDesc {
+            bodies,
+            mesh_paths,
+            light_intensity: get_f32(&globals, "light_intensity", 1.0)?,
+            ambient_strength: get_f32(&globals, "ambient_strength", 0.18)?,
+            emission_strength: get_f32(&globals, "emission_strength", 1.2)?,
+            exposure,
+            tone_mapping: match get_str(&globals, "tone_mapping")? {
+                None => ToneMapping::Aces,
+                Some(name) => ToneMapping::from_name(name)
+                    .ok_or_else(|| format!("`tone_mapping` desconocido `{}` (clamp, reinhard, aces)", name))?,
+            },
+            bloom: parse_bloom(&globals)?,
+        })
+    }
+
+    fn parse_body(
+        table: &Table,
+        previous: &[BodyDesc],
+        base_dir: &Path,
+        registry: &ShaderRegistry,
+        mesh_paths: &mut Vec<String>,
+    ) -> Result<BodyDesc, String> {
+        let name = get_str(table, "name")?.ok_or("falta `name`")?.to_string();
+        if previous.iter().any(|b| b.name == name) {
+            return Err(format!("nombre repetido `{}`", name));
+        }
+
+        let parent = match get_str(table, "parent")? {
+            None => None,
+            Some(parent) => Some(
+                previous
+                    .iter()
+                    .position(|b| b.name == parent)
+                    .ok_or_else(|| format!("el padre `{}` debe declararse antes", parent))?,
+            ),
+        };
+
+        let mesh_file = get_str(table, "mesh")?.ok_or("falta `mesh`")?;
+        let mesh_path = base_dir.join(mesh_file).to_string_lossy().into_owned();
+        let mesh = match mesh_paths.iter().position(|p| *p == mesh_path) {
+            Some(i) => i,
+            None => {
+                mesh_paths.push(mesh_path);
+                mesh_paths.len() - 1
+            }
+        };
+
+        let shader_name = get_str(table, "shader")?.ok_or("falta `shader`")?;
+        // Sin `seed`, cada cuerpo usa su posición en el archivo. El tope mantiene
+        // la semilla exacta en el f32 con que se lee el número
+        let seed = get_f32(table, "seed", previous.len() as f32)?;
+        if !(0.0..=MAX_SEED as f32).contains(&seed) || seed.fract() != 0.0 {
+            return Err(format!("`seed` debe ser un entero entre 0 y {}", MAX_SEED));
+        }
+
+        // La semilla baraja el ruido del shader: dos cuerpos con el mismo look no se ven iguales
+        let params = Self::parse_shader_params(table, base_dir)?.with_seed(seed as u64);
+        let shader = registry.create(shader_name, &params)?;
+
+        let white = Vector3::new(1.0, 1.0, 1.0);
+
+        // Material: el del shader, con lo que el cuerpo sobrescriba
+        let defaults = shader.material();
+        let material = Material {
+            specular: get_f32(table, "specular", defaults.specular)?,
+            shininess: get_f32(table, "shininess", defaults.shininess)?,
+            metallic: get_f32(table, "metallic", defaults.metallic)?.clamp(0.0, 1.0),
+        };
+
+        // Mezcla: la del shader salvo que el cuerpo la cambie
+        let mut blend = match get_str(table, "blend")? {
+            None => BlendState::new(shader.blend_mode()),
+            Some(name) => BlendState::new(BlendMode::from_name(name).ok_or_else(|| {
+                format!("`blend` desconocido `{}` (opaque, alpha, additive, multiply)", name)
+            })?),
+        };
+        if table.contains_key("depth_write") {
+            blend.depth_write = get_bool(table, "depth_write")?;
+        }
+
+        Ok(BodyDesc {
+            label: get_str(table, "label")?.map(str::to_string),
+            label_color: get_vec3(table, "label_color", white)?,
+            parent,
+            mesh,
+            shader_name: shader_name.to_string(),
+            shader,
+            scale: get_f32(table, "scale", 1.0)?,
+            orbit: Self::parse_orbit(table)?,
+            rotation: get_vec3(table, "rotation", Vector3::zero())?,
+            rotation_speed: get_vec3(table, "rotation_speed", Vector3::zero())?,
+            color1: get_color(table, "color1", white)?,
+            color2: get_color(table, "color2", white)?,
+            seed: seed as u32,
+            blend,
+            material,
+            atmosphere: Self::parse_atmosphere(table, registry)?,
+            warp: get_bool(table, "warp")?,
+            name,
+        })
+    }
+
+    // Cualquier clave `atmosphere_*` le da atmósfera al cuerpo
+    fn parse_atmosphere(table: &Table, registry: &ShaderRegistry) -> Result<Option<AtmosphereDesc>, String> {
+        if !table.keys().any(|k| k.starts_with("atmosphere_")) {
+            return Ok(None);
+        }
+        let numbers = HashMap::from([
+            ("thickness".to_string(), get_f32(table, "atmosphere_thickness", 0.08)?),
+            ("density".to_string(), get_f32(table, "atmosphere_density", 1.0)?),
+        ]);
+        let params = ShaderParams::new(numbers, HashMap::new(), Default::default());
+        Ok(Some(AtmosphereDesc {
+            color: get_color(table, "atmosphere_color", Vector3::new(0.35, 0.6, 1.0))?,
+            sunset_color: get_color(table, "atmosphere_sunset", Vector3::new(1.0, 0.45, 0.2))?,
+            shader: registry.create("atmosphere", &params)?,
+        }))
+    }
+
+    fn parse_shader_params(table: &Table, base_dir: &Path) -> Result<ShaderParams, String> {
+        let mut numbers = HashMap::new();
+        let mut texts = HashMap::new();
+        for (key, value) in table {
+            if let Some(param) = key.strip_prefix(PARAMS_PREFIX) {
+                match value {
+                    Value::Num(n) => {
+                        numbers.insert(param.to_string(), *n);
+                    }
+                    Value::Str(text) => {
+                        texts.insert(param.to_string(), text.clone());
+                    }
+                    _ => return Err(format!("`{}` debe ser un número o un texto", key)),
+                }
+            }
+        }
+        Ok(ShaderParams::new(numbers, texts, base_dir.to_path_buf()))
+    }
+
+    fn parse_orbit(table: &Table) -> Result<OrbitalElements, String> {
+        let eccentricity = get_f32(table, "eccentricity", 0.0)?;
+        if !(0.0..1.0).contains(&eccentricity) {
+            return Err("`eccentricity` debe estar en [0, 1)".to_string());
+        }
+
+        Ok(OrbitalElements {
+            eccentricity,
+            inclination: get_f32(table, "inclination", 0.0)?,
+            ascending_node: get_f32(table, "ascending_node", 0.0)?,
+            arg_periapsis: get_f32(table, "arg_periapsis", 0.0)?,
+            ..OrbitalElements::circular(
+                get_f32(table, "orbit_radius", 0.0)?,
+                get_f32(table, "orbit_speed", 0.0)?,
+                get_f32(table, "orbit_phase", 0.0)?,
+            )
+        })
+    }
+
+    // Cuerpos con tecla de warp, en el orden del archivo
+    pub fn warp_bodies(&self) -> Vec<usize> {
+        (0..self.bodies.len()).filter(|&i| self.bodies[i].warp).collect()
+    }
+
+    // Posición relativa al padre según sus elementos orbitales
+    pub fn orbit_offset(&self, body: usize, t: f64) -> Vector3 {
+        self.bodies[body].orbit.position(t)
+    }
+
+    pub fn rotation(&self, body: usize, t: f64) -> Vector3 {
+        let body = &self.bodies[body];
+        let axis = |angle: f32, speed: f32| wrap_angle(angle as f64 + speed as f64 * t);
+        Vector3::new(
+            axis(body.rotation.x, body.rotation_speed.x),
+            axis(body.rotation.y, body.rotation_speed.y),
+            axis(body.rotation.z, body.rotation_speed.z),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_inside_string_is_not_a_comment() {
+        let text = "# sistema de prueba\n\
+                    [[body]]\n\
+                    name  = \"p3\"\n\
+                    label = \"Planeta #3\"  # comentario después del texto\n\
+                    mesh  = \"mallas/#esfera.obj\"\n";
+        let (_, bodies) = parse_tables(text).unwrap();
+        let (_, body) = &bodies[0];
+        assert_eq!(get_str(body, "label").unwrap(), Some("Planeta #3"));
+        assert_eq!(get_str(body, "mesh").unwrap(), Some("mallas/#esfera.obj"));
+    }
+
+    fn parse(text: &str) -> Result<SystemDesc, String> {
+        SystemDesc::parse(text, Path::new(""), &ShaderRegistry::builtin())
+    }
+
+    // Un sistema mínimo válido con `extra` agregado al final del cuerpo
+    fn with_body(extra: &str) -> Result<SystemDesc, String> {
+        parse(&format!(
+            "[[body]]\nname = \"sol\"\nmesh = \"planeta.obj\"\nshader = \"star\"\n{}\n",
+            extra
+        ))
+    }
+
+    fn assert_err(result: Result<SystemDesc, String>, fragment: &str) {
+        match result {
+            Ok(_) => panic!("se esperaba un error con `{}`", fragment),
+            Err(e) => assert!(e.contains(fragment), "error `{}` no menciona `{}`", e, fragment),
+        }
+    }
+
+    #[test]
+    fn minimal_system_loads() {
+        let system = with_body("").unwrap();
+        assert_eq!(system.bodies.len(), 1);
+        assert_eq!(system.bodies[0].name, "sol");
+    }
+
+    #[test]
+    fn escaped_quotes_in_strings() {
+        let system = with_body(r##"label = "a\"#b\\c"  # comentario"##).unwrap();
+        assert_eq!(system.bodies[0].label.as_deref(), Some(r##"a"#b\c"##));
+        assert_err(with_body(r##"label = "a\q""##), "escape no admitido");
+        assert_err(with_body(r##"label = "sin cerrar"##), "sin cerrar");
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert_err(with_body("radio = 3"), "clave desconocida `radio`");
+        assert_err(parse("brillo = 1\n[[body]]\n"), "clave desconocida `brillo`");
+        assert_err(with_body("warp = true\nwarp = false"), "clave repetida `warp`");
+    }
+
+    #[test]
+    fn shader_params_prefix() {
+        assert!(with_body("params.glow = 0.3").is_ok());
+        // Un parámetro que el shader no conoce lo rechaza el registro
+        assert!(with_body("params.nope = 1").is_err());
+        // Fuera de un [[body]] no hay shader al que pasárselo
+        assert_err(parse("params.glow = 0.3\n"), "clave desconocida `params.glow`");
+    }
+
+    #[test]
+    fn parent_must_come_first() {
+        let text = "[[body]]\nname = \"luna\"\nparent = \"tierra\"\nmesh = \"m.obj\"\nshader = \"flat\"\n\
+                    [[body]]\nname = \"tierra\"\nmesh = \"m.obj\"\nshader = \"flat\"\n";
+        assert_err(parse(text), "debe declararse antes");
+    }
+
+    #[test]
+    fn unknown_shader_is_rejected() {
+        assert!(parse("[[body]]\nname = \"x\"\nmesh = \"m.obj\"\nshader = \"plasma\"\n").is_err());
+    }
+
+    #[test]
+    fn eccentricity_must_be_below_one() {
+        assert!(with_body("eccentricity = 0.99").is_ok());
+        assert_err(with_body("eccentricity = 1.0"), "eccentricity");
+        assert_err(with_body("eccentricity = -0.1"), "eccentricity");
+    }
+
+    #[test]
+    fn bloom_levels_range() {
+        let levels = |n: &str| parse(&format!("bloom_levels = {}\n[[body]]\nname = \"s\"\nmesh = \"m.obj\"\nshader = \"star\"\n", n));
+        assert_eq!(levels("3").unwrap().bloom.levels, 3);
+        assert_err(levels("0"), "bloom_levels");
+        assert_err(levels("9"), "bloom_levels");
+        assert_err(levels("2.5"), "bloom_levels");
+    }
+
+    #[test]
+    fn exposure_must_be_positive() {
+        let exposure = |e: &str| parse(&format!("exposure = {}\n[[body]]\nname = \"s\"\nmesh = \"m.obj\"\nshader = \"star\"\n", e));
+        assert_eq!(exposure("2").unwrap().exposure, 2.0);
+        assert_err(exposure("0"), "exposure");
+        assert_err(exposure("-1"), "exposure");
+    }
+
+    #[test]
+    fn unsupported_toml_is_explicit() {
+        assert_err(with_body("scale = 1_000"), "separadores");
+        assert_err(with_body("rotation = [0.0,"), "misma línea");
+        assert_err(with_body("rotation = { x = 1 }"), "tablas en línea");
+        assert_err(with_body("label = 'simple'"), "comillas dobles");
+        assert_err(with_body("[material]"), "tabla no admitida");
+        assert_err(with_body("scale = nan"), "valor inválido");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::create_model_matrix;
+use crate::scene::SystemDesc;
+use raylib::prelude::*;
+
+// Qué representa cada nodo del árbol
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeKind {
+    Group,        // solo agrupa (raíz)
+    Orbit(usize), // marco orbital del cuerpo: se traslada, no gira ni escala
+    Body(usize),  // malla del cuerpo: escala y rotación propia
+}
+
+pub struct SceneNode {
+    pub kind: NodeKind,
+    pub translation: Vector3,
+    pub scale: f32,
+    pub rotation: Vector3,
+    pub children: Vec<SceneNode>,
+}
+
+impl SceneNode {
+    pub fn new(kind: NodeKind) -> Self {
+        SceneNode {
+            kind,
+            translation: Vector3::zero(),
+            scale: 1.0,
+            rotation: Vector3::zero(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Construye el árbol del sistema. Cada cuerpo aporta un nodo `Orbit` con su
+    /// malla (`Body`) y los marcos orbitales de sus hijos colgando de él, así que
+    /// lunas y anillos heredan la posición del padre pero no su giro ni su escala.
+    pub fn from_system(system: &SystemDesc) -> Self {
+        let mut root = SceneNode::new(NodeKind::Group);
+        root.children = Self::orbit_nodes(system, None);
+        root
+    }
+
+    fn orbit_nodes(system: &SystemDesc, parent: Option<usize>) -> Vec<SceneNode> {
+        (0..system.bodies.len())
+            .filter(|&i| system.bodies[i].parent == parent)
+            .map(|i| {
+                let mut orbit = SceneNode::new(NodeKind::Orbit(i));
+                orbit.children.push(SceneNode::new(NodeKind::Body(i)));
+                orbit.children.extend(Self::orbit_nodes(system, Some(i)));
+                orbit
+            })
+            .collect()
+    }
+
+    // Actualiza las transformaciones locales para el tiempo t
+    pub fn update(&mut self, system: &SystemDesc, t: f64) {
+        match self.kind {
+            NodeKind::Group => {}
+            NodeKind::Orbit(i) => self.translation = system.orbit_offset(i, t),
+            NodeKind::Body(i) => {
+                self.scale = system.bodies[i].scale;
+                self.rotation = system.rotation(i, t);
+            }
+        }
+
+        for child in &mut self.children {
+            child.update(system, t);
+        }
+    }
+
+    pub fn local_matrix(&self) -> Matrix {
+        create_model_matrix(self.translation, self.scale, self.rotation)
+    }
+
+    /// Recorre el árbol en profundidad entregando cada nodo con su matriz de mundo
+    pub fn traverse<F: FnMut(&SceneNode, &Matrix)>(&self, parent_world: &Matrix, visit: &mut F) {
+        // Con la convención de raylib, `local * padre` aplica primero lo local
+        let world = self.local_matrix() * *parent_world;
+        visit(self, &world);
+
+        for child in &self.children {
+            child.traverse(&world, visit);
+        }
+    }
+
+    // Matriz de mundo de cada cuerpo dibujable, en orden de recorrido
+    pub fn body_transforms(&self) -> Vec<(usize, Matrix)> {
+        let mut out = Vec::new();
+        self.traverse(&Matrix::identity(), &mut |node, world| {
+            if let NodeKind::Body(i) = node.kind {
+                out.push((i, *world));
+            }
+        });
+        out
+    }
+}
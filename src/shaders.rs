@@ -1,6 +1,15 @@
 use raylib::prelude::*;
 use crate::vertex::Vertex;
+use crate::fragment::Fragment;
 use crate::Uniforms;
+use crate::framebuffer::BlendMode;
+use crate::matrix::{clip_to_screen, multiply_matrix_vector4};
+use crate::noise::Noise;
+use crate::shadow::{self, ShadowShape};
+use crate::texture::{Texture, WrapMode};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 // ---------- Utilidades matemáticas seguras ----------
 fn v3(x: f32, y: f32, z: f32) -> Vector3 {
@@ -22,15 +31,6 @@ fn normalize(v: Vector3) -> Vector3 {
 }
 // ----------------------------------------------------
 
-fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
-    Vector4::new(
-        matrix.m0 * vector.x + matrix.m4 * vector.y + matrix.m8 * vector.z + matrix.m12 * vector.w,
-        matrix.m1 * vector.x + matrix.m5 * vector.y + matrix.m9 * vector.z + matrix.m13 * vector.w,
-        matrix.m2 * vector.x + matrix.m6 * vector.y + matrix.m10 * vector.z + matrix.m14 * vector.w,
-        matrix.m3 * vector.x + matrix.m7 * vector.y + matrix.m11 * vector.z + matrix.m15 * vector.w,
-    )
-}
-
 // Iluminación Lambert simple
 fn lambert_light(normal: Vector3, light_dir: Vector3) -> Vector3 {
     let n = normalize(normal);
@@ -39,114 +39,727 @@ fn lambert_light(normal: Vector3, light_dir: Vector3) -> Vector3 {
     v3(intensity, intensity, intensity)
 }
 
-// Star: c_core y c_outer vienen de uniforms.base_color1/2
-fn shade_star(pos: Vector3, c_core: Vector3, c_outer: Vector3) -> Vector3 {
-    // r^2 en espacio de modelo (para esfera unitaria suele rondar 1.0 en la superficie)
-    let r2 = pos.x * pos.x + pos.y * pos.y + pos.z * pos.z;
-    let glow = (1.5 - r2).clamp(0.0, 1.0); // caída suave y más brillante
-    c_core * (0.85 + 0.15 * glow) + c_outer * 0.1
+// Parámetros propios de un shader, leídos de las claves `params.*` del cuerpo
+#[derive(Clone, Debug, Default)]
+pub struct ShaderParams {
+    numbers: HashMap<String, f32>,
+    texts: HashMap<String, String>,
+    base_dir: PathBuf, // carpeta del archivo del sistema, para rutas relativas
+    seed: u64,         // semilla del cuerpo: baraja la tabla del ruido
 }
 
-// Rocky: c_base y c_dark desde uniforms
-fn hash(x: f32) -> f32 {
-    ((x * 34.0).sin() * 143758.5453).fract().abs()
+impl ShaderParams {
+    pub fn new(numbers: HashMap<String, f32>, texts: HashMap<String, String>, base_dir: PathBuf) -> Self {
+        ShaderParams {
+            numbers,
+            texts,
+            base_dir,
+            seed: 0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get(&self, key: &str, default: f32) -> f32 {
+        self.numbers.get(key).copied().unwrap_or(default)
+    }
+
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        self.texts.get(key).map(String::as_str)
+    }
+
+    // Ruta de un archivo, relativa a la carpeta del sistema
+    pub fn get_path(&self, key: &str) -> Option<String> {
+        self.get_text(key)
+            .map(|file| self.base_dir.join(file).to_string_lossy().into_owned())
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.numbers.keys().chain(self.texts.keys())
+    }
 }
-fn shade_rocky(pos: Vector3, c_base: Vector3, c_dark: Vector3) -> Vector3 {
-    // ruido baratito
-    let h = hash(pos.x + hash(pos.y + hash(pos.z)));
-    // mezcla entre base y oscuro en función del ruido
-    c_base * (0.8 + 0.2 * h) + c_dark * (0.4 * h)
+
+// Respuesta especular de una superficie (Blinn-Phong)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    pub specular: f32,  // intensidad del brillo (0 = mate)
+    pub shininess: f32, // exponente: más alto = brillo más chico y nítido
+    pub metallic: f32,  // 0 = brillo blanco (agua, plástico), 1 = brillo del tono base (metal)
 }
 
-// Gas Giant: bandas mezclando c1/c2
-fn shade_gas_giant(pos: Vector3, c1: Vector3, c2: Vector3) -> Vector3 {
-    let bands = (pos.y * 10.0).sin() * 0.5 + 0.5;
-    c1 * bands + c2 * (1.0 - bands)
+impl Material {
+    pub const MATTE: Material = Material {
+        specular: 0.0,
+        shininess: 16.0,
+        metallic: 0.0,
+    };
 }
 
-fn shade_cyber(pos: Vector3, c1: Vector3, c2: Vector3) -> Vector3 {
-    let grid = ((pos.x * 15.0).sin().abs() > 0.95 ||
-                (pos.y * 15.0).sin().abs() > 0.95 ||
-                (pos.z * 15.0).sin().abs() > 0.95) as i32;
+/// Un tipo de material. Cada shader guarda sus propios parámetros y el
+/// rasterizador lo usa desde varios hilos a la vez (de ahí `Send + Sync`).
+pub trait PlanetShader: Send + Sync {
+    /// Hook de vértice; por defecto la transformación estándar
+    fn vertex(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+        vertex_shader(vertex, uniforms)
+    }
 
-    // Si coincide con la "rejilla", usa color neon
-    if grid == 1 {
-        c2 * 1.5 // brillante
-    } else {
-        c1 // metal oscuro
+    /// Color final del fragmento
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3;
+
+    /// Color y opacidad (alpha en [0, 1]); los shaders transparentes lo
+    /// reimplementan. Un alpha de 0 descarta el fragmento.
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> (Vector3, f32) {
+        (self.fragment(fragment, uniforms), 1.0)
+    }
+
+    /// Material por defecto; el cuerpo puede cambiarlo con `specular`, `shininess` y `metallic`
+    fn material(&self) -> Material {
+        Material::MATTE
+    }
+
+    /// Modo de mezcla por defecto; el cuerpo puede cambiarlo con `blend = "..."`
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Opaque
     }
-}
 
-fn shade_magma(pos: Vector3, lava: Vector3, rock: Vector3) -> Vector3 {
-    let h = hash(pos.x + hash(pos.y + hash(pos.z)));
+    /// Forma con la que el cuerpo da sombra (None = no tapa la luz)
+    fn shadow_shape(&self) -> Option<ShadowShape> {
+        Some(ShadowShape::Sphere)
+    }
+
+    /// Cuánta luz tapa la superficie en un punto (espacio de objeto), para las sombras
+    fn opacity_at(&self, _position: Vector3) -> f32 {
+        1.0
+    }
+
+    /// Los cuerpos emisivos no se iluminan; el primero del sistema es la fuente de luz
+    fn is_emissive(&self) -> bool {
+        false
+    }
+}
 
-    // lava donde el ruido es grande
-    let mix = (h * 2.0 - 0.9).clamp(0.0, 1.0);
-    lava * mix + rock * (1.0 - mix)
+// Iluminación común: Ambiente + Difusa + Especular de la luz puntual (el sol)
+fn lit(base_color: Vector3, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    lit_specular(base_color, 1.0, fragment, uniforms)
 }
 
-fn shade_flat(_pos: Vector3, color: Vector3) -> Vector3 {
+// Igual que `lit`, pero `specular_mask` (0 a 1) apaga el brillo en las zonas
+// mate del cuerpo (p. ej. la tierra firme frente al océano)
+fn lit_specular(base_color: Vector3, specular_mask: f32, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+    let light_dir = uniforms.light_position - fragment.world_position;
+    let lambert = lambert_light(fragment.normal, light_dir);
+    let material = uniforms.material;
+
+    // Lo que tapa el sol (anillos, otros cuerpos) apaga la difusa y el brillo, no el ambiente
+    let visibility = shadow::light_visibility(
+        &uniforms.occluders,
+        fragment.world_position,
+        uniforms.light_position,
+        uniforms.light_radius,
+    );
+    let lambert = lambert * visibility;
+
+    // color_final = base*(ambiente) + base*(difusa*intensidad); el metal difunde menos
+    let metallic = material.metallic * specular_mask;
+    let diffuse = lambert * (uniforms.light_intensity * (1.0 - 0.5 * metallic));
+    let mut color = base_color * uniforms.ambient_strength + base_color * diffuse;
+
+    // Blinn-Phong: brillo donde la normal apunta al medio entre la luz y la cámara
+    let strength = material.specular * specular_mask;
+    if strength > 0.0 && lambert.x > 0.0 {
+        let n = normalize(fragment.normal);
+        let l = normalize(light_dir);
+        let v = normalize(uniforms.camera_position - fragment.world_position);
+        let h = normalize(l + v);
+        let highlight = dot(n, h).max(0.0).powf(material.shininess);
+
+        // El metal tiñe el brillo con el tono (no la oscuridad) de su color base
+        let peak = base_color.x.max(base_color.y).max(base_color.z).max(1e-3);
+        let tint = mix(v3(1.0, 1.0, 1.0), base_color / peak, metallic);
+        color += tint * (strength * highlight * uniforms.light_intensity);
+    }
     color
 }
 
-fn shade_ring(pos: Vector3, inner: Vector3, outer: Vector3) -> Vector3 {
-    // distancia radial
-    let r = (pos.x * pos.x + pos.z * pos.z).sqrt();
+// Star: color1 = núcleo, color2 = borde. EMISIÓN pura (no multiplicar por Lambert)
+pub struct StarShader {
+    pub noise: Noise,
+    pub glow: f32,          // cuánto aclara el centro
+    pub granulation: f32,   // contraste de los gránulos de convección
+    pub granule_scale: f32, // frecuencia de los gránulos
+}
+
+impl PlanetShader for StarShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.vertex_position;
+        // r^2 en espacio de modelo (para esfera unitaria suele rondar 1.0 en la superficie)
+        let r2 = pos.x * pos.x + pos.y * pos.y + pos.z * pos.z;
+        let glow = (1.5 - r2).clamp(0.0, 1.0); // caída suave y más brillante
+
+        // Granulación: celdas brillantes con bordes oscuros que se deforman con el tiempo
+        let t = uniforms.time;
+        let p = pos * self.granule_scale;
+        let churn = Vector3::new(t * 0.31, t * -0.23, t * 0.17);
+        let (f1, f2) = self.noise.worley(p + churn * 0.5);
+        let cells = smoothstep(0.0, 0.35, f2 - f1);
+        let flicker = self.noise.perlin(p * 0.5 + churn) * 0.5 + 0.5;
+        let surface = 1.0 - self.granulation * (1.0 - cells * (0.7 + 0.3 * flicker));
+
+        let color = uniforms.base_color1 * ((1.0 - self.glow + self.glow * glow) * surface)
+            + uniforms.base_color2 * 0.1;
+        color * uniforms.emission_strength
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+
+    fn shadow_shape(&self) -> Option<ShadowShape> {
+        None // la fuente de luz no se tapa a sí misma
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn mix(a: Vector3, b: Vector3, t: f32) -> Vector3 {
+    a * (1.0 - t) + b * t
+}
+
+// Rocky: océanos (color1) y continentes (color2) con cordilleras
+pub struct RockyShader {
+    pub noise: Noise,
+    pub continent_scale: f32, // frecuencia de los continentes sobre la esfera unitaria
+    pub sea_level: f32,       // en [-1, 1]: más alto = más océano
+    pub mountains: f32,       // cuánto se notan las cordilleras
+}
+
+impl PlanetShader for RockyShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let p = fragment.vertex_position * self.continent_scale;
+        let height = self.noise.fbm(p, 5, 2.0, 0.5);
+
+        let ocean = height < self.sea_level;
+        let base = if ocean {
+            // Océano: más oscuro cuanto más profundo
+            let depth = smoothstep(0.0, 0.35, self.sea_level - height);
+            uniforms.base_color1 * (1.0 - 0.45 * depth)
+        } else {
+            // Tierra: las crestas suben hacia roca clara lejos de la costa
+            let inland = smoothstep(0.0, 0.25, height - self.sea_level);
+            let ridges = self.noise.ridged(p * 2.5, 4, 2.0, 0.5);
+            let rock = v3(0.196, 0.147, 0.107); // colores en luz lineal
+            let beach = 1.0 - smoothstep(0.0, 0.03, height - self.sea_level);
+            let shore = mix(uniforms.base_color2, v3(0.538, 0.448, 0.214), beach);
+            mix(shore, rock, (ridges * inland * self.mountains).clamp(0.0, 1.0))
+        };
+        // Solo el agua refleja el sol
+        lit_specular(base, if ocean { 1.0 } else { 0.0 }, fragment, uniforms)
+    }
+
+    fn material(&self) -> Material {
+        Material {
+            specular: 0.6,
+            shininess: 60.0,
+            metallic: 0.0,
+        }
+    }
+}
+
+// Gas Giant: bandas (color1/color2) que derivan con rotación diferencial y una tormenta
+pub struct GasGiantShader {
+    pub noise: Noise,
+    pub band_frequency: f32,
+    pub drift_speed: f32, // velocidad de las corrientes (rad por unidad de tiempo)
+    pub turbulence: f32,  // cuánto ondulan los bordes de las bandas
+    pub storm_size: f32,  // radio de la gran tormenta (0 = sin tormenta)
+}
+
+impl PlanetShader for GasGiantShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.vertex_position;
+        let t = uniforms.time;
+
+        // Rotación diferencial: cada latitud gira a su velocidad, en sentidos alternos
+        let drift = t * self.drift_speed * (pos.y * self.band_frequency * 0.5).sin();
+        let (sin_d, cos_d) = drift.sin_cos();
+        let flowing = v3(pos.x * cos_d - pos.z * sin_d, pos.y, pos.x * sin_d + pos.z * cos_d);
+
+        // Turbulencia que deforma la latitud de las bandas
+        let p = flowing * 3.0;
+        let swirl = self.noise.fbm(p + v3(0.0, t * 0.05, 0.0), 4, 2.0, 0.5);
+        let mut latitude = pos.y + self.turbulence * swirl;
+
+        // Gran tormenta: óvalo que da la vuelta al planeta y retuerce las bandas
+        let mut storm = 0.0;
+        if self.storm_size > 0.0 {
+            let longitude = t * self.drift_speed * 0.3;
+            let center = v3(longitude.cos() * 0.86, -0.35, longitude.sin() * 0.86);
+            let d = pos - center;
+            let dist2 = (d.x * d.x + d.z * d.z) / (self.storm_size * self.storm_size)
+                + d.y * d.y / (self.storm_size * self.storm_size * 0.36);
+            storm = (-dist2 * 2.0).exp();
+            latitude += storm * (dist2.sqrt() * 6.0 - t * 2.0).sin() * 0.05;
+        }
+
+        let bands = (latitude * self.band_frequency).sin() * 0.5 + 0.5;
+        let mut base = uniforms.base_color1 * bands + uniforms.base_color2 * (1.0 - bands);
+        base = mix(base, v3(0.522, 0.100, 0.040), storm * 0.8);
+        lit(base, fragment, uniforms)
+    }
+}
+
+// Cyber: metal oscuro (color1) con rejilla de neón (color2) que se desplaza y pulsa
+pub struct CyberShader {
+    pub grid_frequency: f32,
+    pub line_width: f32, // umbral de |sin|: más bajo = líneas más gruesas
+    pub glow: f32,
+    pub scroll_speed: f32, // las líneas horizontales suben con el tiempo
+}
+
+impl PlanetShader for CyberShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.vertex_position;
+        let f = self.grid_frequency;
+        let t = uniforms.time;
+        let scroll = t * self.scroll_speed;
+        let grid = (pos.x * f).sin().abs() > self.line_width
+            || ((pos.y - scroll) * f).sin().abs() > self.line_width
+            || (pos.z * f).sin().abs() > self.line_width;
+
+        // Metal oscuro con reflejos en todo el planeta
+        let metal = lit(uniforms.base_color1, fragment, uniforms);
+
+        // Si coincide con la "rejilla", el neón emite luz propia (también del lado
+        // de noche o en sombra) con un pulso que recorre el planeta
+        if grid {
+            let pulse = 0.75 + 0.25 * (pos.y * 4.0 - t * 3.0 + uniforms.seed as f32).sin();
+            let neon = uniforms.base_color2 * (self.glow * pulse);
+            metal + neon * uniforms.emission_strength
+        } else {
+            metal
+        }
+    }
+
+    fn material(&self) -> Material {
+        Material {
+            specular: 0.9,
+            shininess: 40.0,
+            metallic: 0.85,
+        }
+    }
+}
+
+// Magma: corteza de roca (color2) partida en placas con lava (color1) en las grietas
+pub struct MagmaShader {
+    pub noise: Noise,
+    pub plate_scale: f32, // frecuencia de las placas
+    pub crack_width: f32, // ancho de las grietas (en distancia entre celdas)
+    pub flow: f32,        // cuánto deforma el ruido las grietas (ríos de lava)
+    pub pulse_speed: f32, // latido de la lava
+}
+
+impl PlanetShader for MagmaShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.vertex_position;
+        let t = uniforms.time;
+
+        // Deformación con simplex para que las grietas serpenteen (y fluyan despacio)
+        let q = pos * 2.0 + v3(0.0, 0.0, t * 0.04);
+        let warp = Vector3::new(
+            self.noise.simplex(q),
+            self.noise.simplex(q + Vector3::new(5.2, 1.3, 7.1)),
+            self.noise.simplex(q + Vector3::new(9.7, 3.4, 2.8)),
+        );
+        let p = (pos + warp * self.flow) * self.plate_scale;
+
+        // Worley: f2 - f1 ≈ 0 en el borde entre dos placas
+        let (f1, f2) = self.noise.worley(p);
+        let crack = 1.0 - smoothstep(0.0, self.crack_width, f2 - f1);
+
+        // Lagos de lava donde el fBm es alto
+        let pools = smoothstep(0.35, 0.6, self.noise.fbm(pos * 1.5, 4, 2.0, 0.5));
+        let lava = crack.max(pools);
+
+        // Latido: ondas de brillo que recorren la lava
+        let phase = self.noise.perlin(pos * 3.0) * 6.0;
+        let pulse = 0.8 + 0.35 * (t * self.pulse_speed + phase).sin();
+
+        // La roca se oscurece hacia el centro de cada placa; la lava brilla sola
+        let rock = uniforms.base_color2 * (0.6 + 0.6 * f1.min(1.0));
+        lit(rock * (1.0 - lava), fragment, uniforms) + uniforms.base_color1 * (lava * pulse)
+    }
+}
+
+// Flat: un solo color (color1)
+pub struct FlatShader;
+
+impl PlanetShader for FlatShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        lit(uniforms.base_color1, fragment, uniforms)
+    }
+}
+
+// Ring: degradado radial del interior (color1) al exterior (color2), semitransparente
+pub struct RingShader {
+    pub inner_radius: f32,  // radio (espacio de modelo) donde empieza el degradado
+    pub falloff: f32,       // 1 / ancho del degradado
+    pub opacity: f32,       // alpha medio del anillo
+    pub gap_frequency: f32, // frecuencia de las divisiones (franjas más finas)
+}
+
+impl PlanetShader for RingShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        self.shade(fragment, uniforms).0
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> (Vector3, f32) {
+        let pos = fragment.vertex_position;
+        // distancia radial
+        let r = (pos.x * pos.x + pos.z * pos.z).sqrt();
+
+        // degradado del color entre interior y exterior del anillo
+        let t = ((r - self.inner_radius) * self.falloff).clamp(0.0, 1.0);
+        let base = uniforms.base_color1 * (1.0 - t) + uniforms.base_color2 * t;
+
+        (lit(base, fragment, uniforms), self.coverage(r))
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Alpha
+    }
+
+    fn shadow_shape(&self) -> Option<ShadowShape> {
+        Some(ShadowShape::Ring)
+    }
+
+    // La sombra del anillo tiene las mismas franjas que el anillo
+    fn opacity_at(&self, position: Vector3) -> f32 {
+        self.coverage((position.x * position.x + position.z * position.z).sqrt())
+    }
+}
+
+impl RingShader {
+    // Alpha a una distancia `r` del centro: franjas más densas y más ralas,
+    // como las divisiones de Saturno
+    fn coverage(&self, r: f32) -> f32 {
+        let bands = (r * self.gap_frequency).sin() * 0.5 + 0.5;
+        (self.opacity * (0.55 + 0.45 * bands * bands)).clamp(0.0, 1.0)
+    }
+}
+
+// Textured: albedo leído de una imagen por UV, teñido por color1
+pub struct TexturedShader {
+    pub albedo: Texture,
+    pub bilinear: bool, // false = texel más cercano
+}
+
+impl PlanetShader for TexturedShader {
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let uv = fragment.tex_coords;
+        let texel = if self.bilinear {
+            self.albedo.sample_bilinear(uv)
+        } else {
+            self.albedo.sample_nearest(uv)
+        };
+        let tint = uniforms.base_color1;
+        lit(v3(texel.x * tint.x, texel.y * tint.y, texel.z * tint.z), fragment, uniforms)
+    }
+}
+
+// Atmosphere: capa de aire alrededor de una esfera de radio 1. color1 = cielo de
+// día, color2 = atardecer. Se suma a lo que hay detrás (aditivo).
+pub struct AtmosphereShader {
+    pub thickness: f32, // espesor relativo al radio del planeta
+    pub density: f32,   // cuánta luz dispersa por unidad de camino
+}
+
+impl PlanetShader for AtmosphereShader {
+    // Infla la esfera: la capa envuelve al planeta con la misma matriz de modelo
+    fn vertex(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+        let mut shell = vertex.clone();
+        shell.position = vertex.position * (1.0 + self.thickness);
+        vertex_shader(&shell, uniforms)
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        self.shade(fragment, uniforms).0
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> (Vector3, f32) {
+        // Esfera analítica (centro y radio salen de la matriz de modelo): la
+        // malla es poligonal y sus normales marcarían las caras
+        let m = &uniforms.model_matrix;
+        let center = v3(m.m12, m.m13, m.m14);
+        let radius = v3(m.m0, m.m1, m.m2).length();
+        let n = normalize(fragment.world_position - center);
+        let v = normalize(uniforms.camera_position - fragment.world_position);
+        let l = normalize(uniforms.light_position - fragment.world_position);
+
+        // Largo del rayo de vista dentro de la capa (en radios del planeta).
+        // d = distancia del rayo al centro: si es < 1 el rayo choca con el
+        // planeta; si no, atraviesa solo el aire y se hace más corto hacia el
+        // borde exterior, que así se desvanece sin corte.
+        let outer = 1.0 + self.thickness;
+        let to_center = (center - uniforms.camera_position) / radius;
+        let along = dot(to_center, v);
+        let d2 = (dot(to_center, to_center) - along * along).max(0.0);
+        let path = if d2 < 1.0 {
+            (outer * outer - d2).sqrt() - (1.0 - d2).sqrt()
+        } else {
+            2.0 * (outer * outer - d2).max(0.0).sqrt()
+        };
+        let alpha = 1.0 - (-self.density * path).exp();
+
+        // Lado de día (la luz rodea un poco el terminador) y tono de atardecer en el borde de la sombra
+        let mu = dot(n, l);
+        let day = smoothstep(-0.3, 0.35, mu);
+        let sunset = smoothstep(0.45, 0.0, mu);
+        let color = mix(uniforms.base_color1, uniforms.base_color2, sunset);
+
+        // Fase de Rayleigh: más brillo mirando hacia el sol o en sentido opuesto
+        let cos_phase = dot(v, l);
+        let phase = 0.75 * (1.0 + cos_phase * cos_phase);
+
+        (color * (day * phase * uniforms.light_intensity), alpha)
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Additive
+    }
+
+    fn shadow_shape(&self) -> Option<ShadowShape> {
+        None // el aire no tapa el sol
+    }
+}
+
+fn wrap_param(params: &ShaderParams, key: &str, default: WrapMode) -> Result<WrapMode, String> {
+    match params.get_text(key) {
+        None => Ok(default),
+        Some(name) => WrapMode::from_name(name)
+            .ok_or_else(|| format!("`{}` debe ser repeat, clamp o mirror", key)),
+    }
+}
+
+fn create_textured(params: &ShaderParams) -> Result<Arc<dyn PlanetShader>, String> {
+    let path = params
+        .get_path("albedo")
+        .ok_or("el shader `textured` necesita `params.albedo`")?;
+    let albedo = Texture::load(&path)?.with_wrap(
+        wrap_param(params, "wrap_u", WrapMode::Repeat)?,
+        wrap_param(params, "wrap_v", WrapMode::Clamp)?,
+    );
+    let bilinear = match params.get_text("filter").unwrap_or("bilinear") {
+        "bilinear" => true,
+        "nearest" => false,
+        _ => return Err("`filter` debe ser bilinear o nearest".to_string()),
+    };
+    Ok(Arc::new(TexturedShader { albedo, bilinear }))
+}
+
+type ShaderFactory = fn(&ShaderParams) -> Result<Arc<dyn PlanetShader>, String>;
+
+struct ShaderEntry {
+    name: &'static str,
+    description: &'static str,
+    params: &'static [&'static str],
+    create: ShaderFactory,
+}
+
+/// Shaders disponibles por nombre. Para agregar un look nuevo basta con
+/// implementar `PlanetShader` y registrarlo en `builtin` (o con `register`).
+pub struct ShaderRegistry {
+    entries: Vec<ShaderEntry>,
+}
+
+impl ShaderRegistry {
+    pub fn new() -> Self {
+        ShaderRegistry { entries: Vec::new() }
+    }
+
+    /// `params` son las claves que acepta el shader; cualquier otra es un error al cargar
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        description: &'static str,
+        params: &'static [&'static str],
+        create: ShaderFactory,
+    ) {
+        self.entries.retain(|e| e.name != name);
+        self.entries.push(ShaderEntry { name, description, params, create });
+    }
+
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(
+            "star",
+            "estrella emisiva con granulacion",
+            &["glow", "granulation", "granule_scale"],
+            |p| {
+                Ok(Arc::new(StarShader {
+                    noise: Noise::new(p.seed()),
+                    glow: p.get("glow", 0.15),
+                    granulation: p.get("granulation", 0.3),
+                    granule_scale: p.get("granule_scale", 9.0),
+                }))
+            },
+        );
+        registry.register(
+            "rocky",
+            "oceanos, continentes y montanas",
+            &["continent_scale", "sea_level", "mountains"],
+            |p| {
+                Ok(Arc::new(RockyShader {
+                    noise: Noise::new(p.seed()),
+                    continent_scale: p.get("continent_scale", 1.6),
+                    sea_level: p.get("sea_level", 0.0),
+                    mountains: p.get("mountains", 1.2),
+                }))
+            },
+        );
+        registry.register(
+            "gas_giant",
+            "bandas que derivan y una gran tormenta",
+            &["band_frequency", "drift_speed", "turbulence", "storm_size"],
+            |p| {
+                Ok(Arc::new(GasGiantShader {
+                    noise: Noise::new(p.seed()),
+                    band_frequency: p.get("band_frequency", 10.0),
+                    drift_speed: p.get("drift_speed", 0.15),
+                    turbulence: p.get("turbulence", 0.06),
+                    storm_size: p.get("storm_size", 0.22),
+                }))
+            },
+        );
+        registry.register(
+            "cyber",
+            "metal con rejilla de neon",
+            &["grid_frequency", "line_width", "glow", "scroll_speed"],
+            |p| {
+                Ok(Arc::new(CyberShader {
+                    grid_frequency: p.get("grid_frequency", 15.0),
+                    line_width: p.get("line_width", 0.95),
+                    glow: p.get("glow", 1.5),
+                    scroll_speed: p.get("scroll_speed", 0.1),
+                }))
+            },
+        );
+        registry.register(
+            "magma",
+            "placas de roca con rios de lava",
+            &["plate_scale", "crack_width", "flow", "pulse_speed"],
+            |p| {
+                Ok(Arc::new(MagmaShader {
+                    noise: Noise::new(p.seed()),
+                    plate_scale: p.get("plate_scale", 4.0),
+                    crack_width: p.get("crack_width", 0.12),
+                    flow: p.get("flow", 0.25),
+                    pulse_speed: p.get("pulse_speed", 2.0),
+                }))
+            },
+        );
+        registry.register("flat", "color liso iluminado", &[], |_| Ok(Arc::new(FlatShader)));
+        registry.register(
+            "ring",
+            "anillo semitransparente con degradado radial",
+            &["inner_radius", "falloff", "opacity", "gap_frequency"],
+            |p| {
+                Ok(Arc::new(RingShader {
+                    inner_radius: p.get("inner_radius", 0.6),
+                    falloff: p.get("falloff", 2.0),
+                    opacity: p.get("opacity", 0.8),
+                    gap_frequency: p.get("gap_frequency", 24.0),
+                }))
+            },
+        );
+        registry.register(
+            "textured",
+            "textura de albedo por UV",
+            &["albedo", "wrap_u", "wrap_v", "filter"],
+            create_textured,
+        );
+        registry.register(
+            "atmosphere",
+            "capa de atmosfera con dispersion en el borde",
+            &["thickness", "density"],
+            |p| {
+                Ok(Arc::new(AtmosphereShader {
+                    thickness: p.get("thickness", 0.08),
+                    density: p.get("density", 1.0),
+                }))
+            },
+        );
+        registry
+    }
+
+    /// Crea el shader `name` con sus parámetros (los que falten toman su valor por defecto)
+    pub fn create(&self, name: &str, params: &ShaderParams) -> Result<Arc<dyn PlanetShader>, String> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| format!("shader desconocido `{}`", name))?;
+
+        let mut keys: Vec<&String> = params.keys().collect();
+        keys.sort();
+        if let Some(key) = keys.into_iter().find(|k| !entry.params.contains(&k.as_str())) {
+            return Err(format!("el shader `{}` no tiene el parámetro `{}`", name, key));
+        }
+
+        (entry.create)(params)
+    }
 
-    // degradado del color entre interior y exterior del anillo
-    let t = ((r - 0.6) * 2.0).clamp(0.0, 1.0);
+    // (nombre, descripción) en orden de registro, para la interfaz
+    pub fn list(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.entries.iter().map(|e| (e.name, e.description))
+    }
+}
 
-    inner * (1.0 - t) + outer * t
+// Igual que `new`: vacío. Los shaders incluidos se piden con `builtin`
+impl Default for ShaderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub fn vertex_shader(v: &Vertex, uniforms: &Uniforms) -> Vertex {
-    // Transformación de posición
+    // Transformación de posición: modelo → vista → proyección
     let pos4 = Vector4::new(v.position.x, v.position.y, v.position.z, 1.0);
-    let t = multiply_matrix_vector4(&uniforms.model_matrix, &pos4);
-    let transformed = if t.w != 0.0 {
-        v3(t.x / t.w, t.y / t.w, t.z / t.w)
-    } else {
-        v3(t.x, t.y, t.z)
-    };
+    let world = multiply_matrix_vector4(&uniforms.model_matrix, &pos4);
+    let view = multiply_matrix_vector4(&uniforms.view_matrix, &world);
+    let clip = multiply_matrix_vector4(&uniforms.projection_matrix, &view);
+
+    // División de perspectiva → NDC → pantalla
+    let transformed = clip_to_screen(&clip, &uniforms.viewport_matrix);
 
-    let world_normal = v.normal;
-let light_dir = v3(0.0, 0.0, -1.0);
-
-let lambert = lambert_light(world_normal, light_dir);
-
-let c1 = uniforms.base_color1;
-let c2 = uniforms.base_color2;
-
-let base_color = match uniforms.shader_type {
-    0 => shade_star(v.position, c1, c2),
-    1 => shade_rocky(v.position, c1, c2),
-    2 => shade_gas_giant(v.position, c1, c2),
-    3 => shade_cyber(v.position, c1, c2),      // ✅ nuevo
-    4 => shade_magma(v.position, c1, c2),      // ✅ nuevo
-    5 => shade_flat(v.position, c1),           // ✅ nuevo
-    6 => shade_ring(v.position, c1, c2),
-    _ => c1,
-};
-
-// Para estrella: EMISIÓN pura
-let color = if uniforms.shader_type == 0 {
-    base_color * uniforms.emission_strength
-} else {
-    // Para rocoso/gaseoso: Ambiente + Difusa
-    let lambert = lambert_light(world_normal, light_dir);
-    // color_final = base*(ambiente) + base*(difusa*intensidad)
-    base_color * uniforms.ambient_strength + base_color * (lambert * uniforms.light_intensity)
-};
-
-// SOLO para estrella: emisivo (no multiplicar por Lambert)
-let color = base_color;
+    // Normal a mundo (w = 0: sin traslación)
+    let n4 = Vector4::new(v.normal.x, v.normal.y, v.normal.z, 0.0);
+    let n = multiply_matrix_vector4(&uniforms.normal_matrix, &n4);
+    let world_normal = normalize(v3(n.x, n.y, n.z));
 
+    // El color se calcula por píxel en fragment_shader
     Vertex {
         position: v.position,
         normal: v.normal,
         tex_coords: v.tex_coords,
-        color,
+        color: v.color,
+        world_position: v3(world.x, world.y, world.z),
+        clip_position: clip,
         transformed_position: transformed,
         transformed_normal: world_normal,
     }
-}
\ No newline at end of file
+}
+
+// Sombreado por píxel: recibe posición, normal y UV interpoladas; devuelve color y alpha
+pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Vector3, f32) {
+    uniforms.shader.shade(fragment, uniforms)
+}
@@ -0,0 +1,181 @@
+use crate::matrix::multiply_matrix_vector4;
+use crate::obj::Obj;
+use crate::shaders::PlanetShader;
+use raylib::prelude::*;
+use std::sync::Arc;
+
+// Sombras analíticas: en vez de un shadow map, cada fragmento lanza un rayo
+// hacia el sol y lo prueba contra formas simples (esferas y anillos planos).
+// El sol es un disco, no un punto: una esfera que lo tapa entero da umbra y una
+// que lo tapa en parte da penumbra (eclipses con borde suave).
+
+// Forma con la que un cuerpo tapa la luz
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShadowShape {
+    Sphere, // planetas y lunas (esfera envolvente de la malla)
+    Ring,   // anillo plano en el plano xz del objeto
+}
+
+// Medidas de la malla en espacio de objeto, calculadas una vez al cargar
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowCaster {
+    shape: ShadowShape,
+    inner_radius: f32, // solo anillos
+    outer_radius: f32,
+}
+
+impl ShadowCaster {
+    pub fn from_mesh(shape: ShadowShape, mesh: &Obj) -> Self {
+        let radii = mesh.vertices.iter().map(|v| {
+            let p = v.position;
+            match shape {
+                ShadowShape::Sphere => p.length(),
+                ShadowShape::Ring => (p.x * p.x + p.z * p.z).sqrt(),
+            }
+        });
+        let (inner_radius, outer_radius) =
+            radii.fold((f32::MAX, 0.0_f32), |(lo, hi), r| (lo.min(r), hi.max(r)));
+        ShadowCaster {
+            shape,
+            inner_radius: if shape == ShadowShape::Ring { inner_radius } else { 0.0 },
+            outer_radius,
+        }
+    }
+
+    /// Radio de la malla medido desde su centro (la esfera que la envuelve)
+    pub fn bounding_radius(mesh: &Obj) -> f32 {
+        Self::from_mesh(ShadowShape::Sphere, mesh).outer_radius
+    }
+
+    /// Ubica la forma en el mundo con la matriz de modelo del frame
+    pub fn place(&self, model: &Matrix, shader: &Arc<dyn PlanetShader>) -> Occluder {
+        let center = Vector3::new(model.m12, model.m13, model.m14);
+        let scale = Vector3::new(model.m0, model.m1, model.m2).length();
+        match self.shape {
+            ShadowShape::Sphere => Occluder::Sphere {
+                center,
+                radius: self.outer_radius * scale,
+            },
+            ShadowShape::Ring => {
+                let up = multiply_matrix_vector4(model, &Vector4::new(0.0, 1.0, 0.0, 0.0));
+                Occluder::Ring {
+                    center,
+                    normal: Vector3::new(up.x, up.y, up.z).normalized(),
+                    inner_radius: self.inner_radius * scale,
+                    outer_radius: self.outer_radius * scale,
+                    to_object: model.inverted(),
+                    shader: shader.clone(),
+                }
+            }
+        }
+    }
+}
+
+// Una forma que tapa la luz, ya en coordenadas de mundo
+#[derive(Clone)]
+pub enum Occluder {
+    Sphere {
+        center: Vector3,
+        radius: f32,
+    },
+    Ring {
+        center: Vector3,
+        normal: Vector3,
+        inner_radius: f32,
+        outer_radius: f32,
+        to_object: Matrix,               // para preguntarle al shader su opacidad en ese punto
+        shader: Arc<dyn PlanetShader>,
+    },
+}
+
+impl Occluder {
+    // Luz del sol (un disco de radio angular `sun_angle` en la dirección `dir`,
+    // a `distance`) que deja pasar hacia `point` (0 = sombra total)
+    fn transmittance(&self, point: Vector3, dir: Vector3, distance: f32, sun_angle: f32) -> f32 {
+        match self {
+            Occluder::Sphere { center, radius } => {
+                let to_center = *center - point;
+                let along = to_center.dot(dir);
+                let center_distance = to_center.length();
+                if along <= 0.0 || center_distance >= distance || center_distance <= *radius {
+                    return 1.0;
+                }
+                // Discos vistos desde el punto: el sol y la esfera, separados `gap` radianes
+                let occluder_angle = (radius / center_distance).asin();
+                let gap = (along / center_distance).clamp(-1.0, 1.0).acos();
+                let covered = disc_overlap(sun_angle, occluder_angle, gap);
+                1.0 - covered / (std::f32::consts::PI * sun_angle * sun_angle)
+            }
+            Occluder::Ring { center, normal, inner_radius, outer_radius, to_object, shader } => {
+                let facing = dir.dot(*normal);
+                if facing.abs() < 1e-6 {
+                    return 1.0;
+                }
+                let t = (*center - point).dot(*normal) / facing;
+                if t <= 1e-3 || t >= distance {
+                    return 1.0;
+                }
+                let hit = point + dir * t;
+                let r = (hit - *center).length();
+                if r < *inner_radius || r > *outer_radius {
+                    return 1.0;
+                }
+                // Las franjas del anillo dejan pasar más o menos luz
+                let local = multiply_matrix_vector4(to_object, &Vector4::new(hit.x, hit.y, hit.z, 1.0));
+                1.0 - shader.opacity_at(Vector3::new(local.x, local.y, local.z))
+            }
+        }
+    }
+}
+
+// Área en común de dos discos de radios `r1` y `r2` con centros a distancia `d`
+// (en ángulos chicos el cielo se puede tratar como un plano)
+fn disc_overlap(r1: f32, r2: f32, d: f32) -> f32 {
+    use std::f32::consts::PI;
+    if d >= r1 + r2 {
+        return 0.0; // no se tocan: a pleno sol
+    }
+    if d <= (r1 - r2).abs() {
+        let r = r1.min(r2);
+        return PI * r * r; // uno dentro del otro: umbra o anillo de luz
+    }
+    // Lente: dos segmentos circulares
+    let a1 = ((d * d + r1 * r1 - r2 * r2) / (2.0 * d * r1)).clamp(-1.0, 1.0).acos();
+    let a2 = ((d * d + r2 * r2 - r1 * r1) / (2.0 * d * r2)).clamp(-1.0, 1.0).acos();
+    let k = ((-d + r1 + r2) * (d + r1 - r2) * (d - r1 + r2) * (d + r1 + r2)).max(0.0).sqrt();
+    r1 * r1 * a1 + r2 * r2 * a2 - 0.5 * k
+}
+
+/// Fracción de la luz del sol que llega a `point` (1 = a pleno sol).
+/// `light_radius` es el radio del sol en mundo: define el ancho de la penumbra.
+pub fn light_visibility(occluders: &[Occluder], point: Vector3, light_position: Vector3, light_radius: f32) -> f32 {
+    let to_light = light_position - point;
+    let distance = to_light.length();
+    if distance <= 0.0 {
+        return 1.0;
+    }
+    let dir = to_light / distance;
+    let sun_angle = (light_radius / distance).clamp(1e-4, 1.0).asin();
+    occluders
+        .iter()
+        .map(|o| o.transmittance(point, dir, distance, sun_angle))
+        .product::<f32>()
+        .clamp(0.0, 1.0)
+}
+
+/// Para cada objeto del frame, las formas que pueden darle sombra: todas
+/// menos la suya (eclipses, tránsitos, sombras de lunas y de anillos).
+/// `placed[i]` es la forma en mundo del objeto i, si tiene.
+pub fn occluders_for(placed: &[Option<Occluder>]) -> Vec<Vec<Occluder>> {
+    (0..placed.len())
+        .map(|receiver| {
+            placed
+                .iter()
+                .enumerate()
+                .filter(|&(caster, _)| caster != receiver)
+                .filter_map(|(_, occluder)| occluder.as_ref())
+                .cloned()
+                .collect()
+        })
+        .collect()
+}
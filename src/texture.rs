@@ -0,0 +1,171 @@
+use crate::tonemap::srgb_to_linear;
+use raylib::prelude::*;
+
+// Qué hacer con coordenadas UV fuera de [0, 1]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapMode {
+    Repeat, // se repite (longitud de un planeta)
+    Clamp,  // se queda en el borde (latitud: los polos no se mezclan)
+    Mirror, // se repite reflejada, sin costura
+}
+
+impl WrapMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "repeat" => Some(WrapMode::Repeat),
+            "clamp" => Some(WrapMode::Clamp),
+            "mirror" => Some(WrapMode::Mirror),
+            _ => None,
+        }
+    }
+
+    // Lleva un índice de texel cualquiera a [0, size)
+    fn apply(self, i: i32, size: usize) -> usize {
+        let n = size as i32;
+        let i = match self {
+            WrapMode::Repeat => i.rem_euclid(n),
+            WrapMode::Clamp => i.clamp(0, n - 1),
+            WrapMode::Mirror => {
+                let period = i.rem_euclid(2 * n);
+                if period < n { period } else { 2 * n - 1 - period }
+            }
+        };
+        i as usize
+    }
+}
+
+// Textura en memoria (colores en [0, 1]); se muestrea desde los shaders en varios hilos
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    texels: Vec<Vector3>, // fila 0 = arriba de la imagen
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+}
+
+impl Texture {
+    pub fn from_pixels(width: usize, height: usize, texels: Vec<Vector3>) -> Self {
+        assert!(width > 0 && height > 0 && texels.len() == width * height);
+        Texture {
+            width,
+            height,
+            texels,
+            wrap_u: WrapMode::Repeat,
+            wrap_v: WrapMode::Clamp,
+        }
+    }
+
+    /// Carga un PNG (o cualquier formato que soporte raylib). No necesita ventana.
+    /// Las imágenes vienen en sRGB; los texels se guardan en luz lineal.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let image = Image::load_image(path).map_err(|e| format!("no se pudo cargar {}: {}", path, e))?;
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let texels = image
+            .get_image_data()
+            .iter()
+            .map(|c| {
+                let channel = |v: u8| srgb_to_linear(v as f32 / 255.0);
+                Vector3::new(channel(c.r), channel(c.g), channel(c.b))
+            })
+            .collect();
+        Ok(Self::from_pixels(width, height, texels))
+    }
+
+    pub fn with_wrap(mut self, wrap_u: WrapMode, wrap_v: WrapMode) -> Self {
+        self.wrap_u = wrap_u;
+        self.wrap_v = wrap_v;
+        self
+    }
+
+    fn texel(&self, x: i32, y: i32) -> Vector3 {
+        let x = self.wrap_u.apply(x, self.width);
+        let y = self.wrap_v.apply(y, self.height);
+        self.texels[y * self.width + x]
+    }
+
+    // v = 0 es el borde inferior de la imagen (convención de los .obj)
+    fn to_texel_space(&self, uv: Vector2) -> (f32, f32) {
+        (uv.x * self.width as f32, (1.0 - uv.y) * self.height as f32)
+    }
+
+    pub fn sample_nearest(&self, uv: Vector2) -> Vector3 {
+        let (x, y) = self.to_texel_space(uv);
+        self.texel(x.floor() as i32, y.floor() as i32)
+    }
+
+    /// Muestreo bilineal: mezcla los 4 texels más cercanos al punto
+    pub fn sample_bilinear(&self, uv: Vector2) -> Vector3 {
+        let (x, y) = self.to_texel_space(uv);
+        // Los centros de los texels están en +0.5
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+
+        let top = self.texel(x0, y0) * (1.0 - tx) + self.texel(x0 + 1, y0) * tx;
+        let bottom = self.texel(x0, y0 + 1) * (1.0 - tx) + self.texel(x0 + 1, y0 + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Vector3 = Vector3 { x: 1.0, y: 0.0, z: 0.0 }; // arriba a la izquierda
+    const B: Vector3 = Vector3 { x: 0.0, y: 1.0, z: 0.0 }; // arriba a la derecha
+    const C: Vector3 = Vector3 { x: 0.0, y: 0.0, z: 1.0 }; // abajo a la izquierda
+    const D: Vector3 = Vector3 { x: 1.0, y: 1.0, z: 1.0 }; // abajo a la derecha
+
+    // Centros de los texels en UV (v = 0 abajo)
+    const LEFT: f32 = 0.25;
+    const RIGHT: f32 = 0.75;
+    const TOP: f32 = 0.75;
+    const BOTTOM: f32 = 0.25;
+
+    fn texture(wrap_u: WrapMode) -> Texture {
+        Texture::from_pixels(2, 2, vec![A, B, C, D]).with_wrap(wrap_u, WrapMode::Clamp)
+    }
+
+    fn assert_close(got: Vector3, expected: Vector3) {
+        assert!((got - expected).length() < 1e-6, "{:?} != {:?}", got, expected);
+    }
+
+    #[test]
+    fn texel_centers_are_exact() {
+        let t = texture(WrapMode::Repeat);
+        for (u, v, color) in [(LEFT, TOP, A), (RIGHT, TOP, B), (LEFT, BOTTOM, C), (RIGHT, BOTTOM, D)] {
+            assert_close(t.sample_nearest(Vector2::new(u, v)), color);
+            assert_close(t.sample_bilinear(Vector2::new(u, v)), color);
+        }
+    }
+
+    #[test]
+    fn bilinear_midpoint_is_the_average() {
+        let t = texture(WrapMode::Clamp);
+        assert_close(t.sample_bilinear(Vector2::new(0.5, 0.5)), (A + B + C + D) * 0.25);
+        // Entre dos centros de la misma fila, mitad y mitad
+        assert_close(t.sample_bilinear(Vector2::new(0.5, TOP)), (A + B) * 0.5);
+    }
+
+    #[test]
+    fn wrap_modes_outside_the_unit_square() {
+        // u = -0.25 y u = 1.25 caen en el centro de un texel fuera de la imagen
+        for (wrap, before, after) in [
+            (WrapMode::Repeat, B, A), // vuelve por el otro lado
+            (WrapMode::Clamp, A, B),  // se queda en el borde
+            (WrapMode::Mirror, A, B), // se refleja en el borde
+        ] {
+            let t = texture(wrap);
+            for sample in [Texture::sample_nearest, Texture::sample_bilinear] {
+                assert_close(sample(&t, Vector2::new(-0.25, TOP)), before);
+                assert_close(sample(&t, Vector2::new(1.25, TOP)), after);
+            }
+        }
+
+        // v se limita por defecto: por debajo de 0 sigue la fila de abajo
+        let t = texture(WrapMode::Repeat);
+        assert_close(t.sample_bilinear(Vector2::new(LEFT, -0.25)), C);
+        assert_close(t.sample_bilinear(Vector2::new(LEFT, 1.25)), A);
+    }
+}
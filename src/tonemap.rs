@@ -0,0 +1,96 @@
+use raylib::math::Vector3;
+
+// Del color HDR lineal del framebuffer a lo que se ve en pantalla:
+// exposición → operador de tone mapping (comprime [0, ∞) a [0, 1]) → sRGB
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToneMapping {
+    Clamp,    // sin compresión: lo que pase de 1 se satura
+    Reinhard, // c / (1 + c): suave, conserva bien los oscuros
+    Aces,     // curva fílmica ACES (aprox. de Narkowicz): más contraste, blancos suaves
+}
+
+impl ToneMapping {
+    pub const ALL: [ToneMapping; 3] = [ToneMapping::Clamp, ToneMapping::Reinhard, ToneMapping::Aces];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "clamp" => Some(ToneMapping::Clamp),
+            "reinhard" => Some(ToneMapping::Reinhard),
+            "aces" => Some(ToneMapping::Aces),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ToneMapping::Clamp => "clamp",
+            ToneMapping::Reinhard => "reinhard",
+            ToneMapping::Aces => "aces",
+        }
+    }
+
+    // Siguiente operador (para cambiarlo con una tecla)
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn apply_channel(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            ToneMapping::Clamp => x.min(1.0),
+            ToneMapping::Reinhard => x / (1.0 + x),
+            ToneMapping::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    pub fn apply(self, color: Vector3) -> Vector3 {
+        Vector3::new(
+            self.apply_channel(color.x),
+            self.apply_channel(color.y),
+            self.apply_channel(color.z),
+        )
+    }
+}
+
+// Curva de transferencia sRGB (lineal en [0, 1] → valor para la pantalla)
+pub fn linear_to_srgb(x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+    if x <= 0.003_130_8 {
+        12.92 * x
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// Inversa: para leer texturas guardadas en sRGB
+pub fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.040_45 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Color escrito a mano (como en un selector de color, en sRGB) a luz lineal
+pub fn srgb_color(color: Vector3) -> Vector3 {
+    Vector3::new(
+        srgb_to_linear(color.x),
+        srgb_to_linear(color.y),
+        srgb_to_linear(color.z),
+    )
+}
+
+/// Color final de pantalla en [0, 1] a partir de un color HDR lineal
+pub fn present(color: Vector3, exposure: f32, tone_mapping: ToneMapping) -> Vector3 {
+    let mapped = tone_mapping.apply(color * exposure);
+    Vector3::new(
+        linear_to_srgb(mapped.x),
+        linear_to_srgb(mapped.y),
+        linear_to_srgb(mapped.z),
+    )
+}
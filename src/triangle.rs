@@ -1,29 +1,43 @@
 use crate::fragment::Fragment;
 use crate::vertex::Vertex;
-use raylib::math::Vector3;
 
 #[inline]
 fn edge(ax: f32, ay: f32, bx: f32, by: f32, px: f32, py: f32) -> f32 {
     (px - ax) * (by - ay) - (py - ay) * (bx - ax)
 }
 
-pub fn triangle(v0: &Vertex, v1: &Vertex, v2: &Vertex) -> Vec<Fragment> {
-    let mut frags = Vec::new();
-
+// Rasteriza el triángulo solo dentro del rectángulo [min, max) (p. ej. un tile)
+// y entrega cada fragmento cubierto a `emit`
+pub fn triangle(
+    v0: &Vertex,
+    v1: &Vertex,
+    v2: &Vertex,
+    min: (i32, i32),
+    max: (i32, i32),
+    mut emit: impl FnMut(Fragment),
+) {
     let p0 = v0.transformed_position;
     let p1 = v1.transformed_position;
     let p2 = v2.transformed_position;
 
     let area = edge(p0.x, p0.y, p1.x, p1.y, p2.x, p2.y);
-    if area.abs() < 1e-6 || area > 0.0 {
-        return frags;
+    // Degenerados y caras traseras: con y hacia abajo, las caras frontales (CCW) tienen área positiva
+    if area.abs() < 1e-6 || area < 0.0 {
+        return;
     }
     let inv_area = 1.0 / area;
 
-    let min_x = p0.x.min(p1.x).min(p2.x).floor() as i32;
-    let max_x = p0.x.max(p1.x).max(p2.x).ceil() as i32;
-    let min_y = p0.y.min(p1.y).min(p2.y).floor() as i32;
-    let max_y = p0.y.max(p1.y).max(p2.y).ceil() as i32;
+    // 1/w de cada vértice: los atributos se interpolan en espacio de pantalla
+    // divididos por w y después se corrigen, si no se deforman con la perspectiva
+    // (la profundidad ya es lineal en pantalla y no necesita corrección)
+    let inv_w0 = 1.0 / v0.clip_position.w;
+    let inv_w1 = 1.0 / v1.clip_position.w;
+    let inv_w2 = 1.0 / v2.clip_position.w;
+
+    let min_x = (p0.x.min(p1.x).min(p2.x).floor() as i32).max(min.0);
+    let max_x = (p0.x.max(p1.x).max(p2.x).ceil() as i32).min(max.0 - 1);
+    let min_y = (p0.y.min(p1.y).min(p2.y).floor() as i32).max(min.1);
+    let max_y = (p0.y.max(p1.y).max(p2.y).ceil() as i32).min(max.1 - 1);
 
     for y in min_y..=max_y {
         for x in min_x..=max_x {
@@ -35,15 +49,33 @@ pub fn triangle(v0: &Vertex, v1: &Vertex, v2: &Vertex) -> Vec<Fragment> {
             let w2 = 1.0 - w0 - w1;
 
             if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-    let depth = p0.z * w0 + p1.z * w1 + p2.z * w2;
-    let color =
-        v0.color * w0 +
-        v1.color * w1 +
-        v2.color * w2;
-    frags.push(Fragment::new(px, py, color, depth));
-}
+                let depth = p0.z * w0 + p1.z * w1 + p2.z * w2;
+
+                // Pesos con corrección de perspectiva (suman 1)
+                let c0 = w0 * inv_w0;
+                let c1 = w1 * inv_w1;
+                let c2 = w2 * inv_w2;
+                let norm = 1.0 / (c0 + c1 + c2);
+                let (c0, c1, c2) = (c0 * norm, c1 * norm, c2 * norm);
+
+                // Atributos para el fragment shader
+                let position = v0.position * c0 + v1.position * c1 + v2.position * c2;
+                let world_position =
+                    v0.world_position * c0 + v1.world_position * c1 + v2.world_position * c2;
+                let normal =
+                    v0.transformed_normal * c0 + v1.transformed_normal * c1 + v2.transformed_normal * c2;
+                let tex_coords = v0.tex_coords * c0 + v1.tex_coords * c1 + v2.tex_coords * c2;
+
+                emit(Fragment::new(
+                    px,
+                    py,
+                    depth,
+                    position,
+                    world_position,
+                    normal,
+                    tex_coords,
+                ));
+            }
         }
     }
-
-    frags
 }
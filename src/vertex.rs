@@ -1,4 +1,4 @@
-use raylib::math::{Vector2, Vector3};
+use raylib::math::{Vector2, Vector3, Vector4};
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -6,6 +6,8 @@ pub struct Vertex {
     pub normal: Vector3,
     pub tex_coords: Vector2,
     pub color: Vector3,
+    pub world_position: Vector3,
+    pub clip_position: Vector4,
     pub transformed_position: Vector3,
     pub transformed_normal: Vector3,
 }
@@ -17,6 +19,8 @@ impl Vertex {
             normal,
             tex_coords,
             color: Vector3::new(1.0, 1.0, 1.0),
+            world_position: position,
+            clip_position: Vector4::new(position.x, position.y, position.z, 1.0),
             transformed_position: position,
             transformed_normal: normal,
         }